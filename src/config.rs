@@ -0,0 +1,55 @@
+use std::{env, fs, path::PathBuf, sync::OnceLock, time::Duration};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+/// User wide settings, read from `$XDG_CONFIG_HOME/runnables/config.toml`
+/// (or `~/.config/runnables/config.toml`).
+#[derive(Deserialize, Default)]
+pub struct Config {
+  /// The shell runfile commands run with when the item doesn't set one,
+  /// eg. `bash` or `python3`. It is called with `-c <command>`. Default: sh
+  pub shell: Option<String>,
+  /// Seconds the runnable gets to exit after Ctrl-C or a signal is forwarded to it,
+  /// before anything left in its process group is killed. Default: 5
+  pub grace_period: Option<f64>,
+}
+
+impl Config {
+  pub fn grace_period(&self) -> Duration {
+    self
+      .grace_period
+      .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+      .unwrap_or(Duration::from_secs(5))
+  }
+}
+
+/// The config, read on first use. A missing file gives the defaults.
+pub fn config() -> &'static Config {
+  static CONFIG: OnceLock<Config> = OnceLock::new();
+  CONFIG.get_or_init(|| match read() {
+    Ok(config) => config,
+    Err(e) => {
+      eprintln!("{e:#}");
+      Config::default()
+    }
+  })
+}
+
+fn read() -> anyhow::Result<Config> {
+  let Some(path) = path() else {
+    return Ok(Config::default());
+  };
+  let Ok(contents) = fs::read_to_string(&path) else {
+    return Ok(Config::default());
+  };
+  toml::from_str(&contents).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+fn path() -> Option<PathBuf> {
+  let dir = match env::var_os("XDG_CONFIG_HOME") {
+    Some(dir) => PathBuf::from(dir),
+    None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+  };
+  Some(dir.join("runnables/config.toml"))
+}
@@ -0,0 +1,99 @@
+use std::{fs, path::Path};
+
+use anyhow::Context;
+use indexmap::IndexMap;
+
+/// Reads the `KEY=value` lines of a dotenv file.
+pub fn read(path: &Path) -> anyhow::Result<IndexMap<String, String>> {
+  let contents =
+    fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+  Ok(parse(&contents))
+}
+
+/// Blank lines and `#` comments are skipped, as is an `export ` in front of the key.
+/// Values can be single quoted (as is) or double quoted (with `\n` escapes),
+/// and unquoted values end at a ` #` comment.
+pub fn parse(contents: &str) -> IndexMap<String, String> {
+  let mut env = IndexMap::new();
+  for line in contents.lines() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let line = line.strip_prefix("export ").unwrap_or(line);
+    let Some((key, value)) = line.split_once('=') else {
+      continue;
+    };
+    let value = value.trim();
+    let value = if let Some(value) = quoted(value, '\'') {
+      value.to_string()
+    } else if let Some(value) = quoted(value, '"') {
+      value.replace("\\n", "\n").replace("\\\"", "\"")
+    } else {
+      match value.find(" #") {
+        Some(comment) => value[..comment].trim_end().to_string(),
+        None => value.to_string(),
+      }
+    };
+    env.insert(key.trim().to_string(), value);
+  }
+  env
+}
+
+fn quoted(value: &str, quote: char) -> Option<&str> {
+  value.strip_prefix(quote)?.strip_suffix(quote)
+}
+
+/// Whether the variable looks like it holds a secret, so its value shouldn't be shown.
+pub fn is_secret(key: &str) -> bool {
+  const SECRETS: &[&str] = &[
+    "SECRET",
+    "TOKEN",
+    "PASSWORD",
+    "PASSWD",
+    "KEY",
+    "PRIVATE",
+    "CREDENTIAL",
+    "AUTH",
+  ];
+  let key = key.to_uppercase();
+  SECRETS.iter().any(|secret| key.contains(secret))
+}
+
+#[cfg(test)]
+mod dotenv_tests {
+  use super::{is_secret, parse};
+
+  #[test]
+  fn parse_dotenv() {
+    let env = parse(
+      r#"
+# comment
+export PORT=8080
+NAME = "runnables cli"
+MULTILINE="a\nb"
+RAW='$HOME \n'
+URL=http://localhost # the url
+EMPTY=
+"#,
+    );
+    let env = env
+      .iter()
+      .map(|(key, value)| (key.as_str(), value.as_str()))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      env,
+      [
+        ("PORT", "8080"),
+        ("NAME", "runnables cli"),
+        ("MULTILINE", "a\nb"),
+        ("RAW", "$HOME \\n"),
+        ("URL", "http://localhost"),
+        ("EMPTY", ""),
+      ]
+    );
+    assert!(is_secret("GITHUB_TOKEN"));
+    assert!(is_secret("db_password"));
+    assert!(!is_secret("PORT"));
+  }
+}
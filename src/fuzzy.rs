@@ -0,0 +1,202 @@
+//! fzf style fuzzy matching, to filter and rank the runnables while searching.
+
+const SCORE_MATCH: i32 = 16;
+const SCORE_GAP_START: i32 = -3;
+const SCORE_GAP_EXTENSION: i32 = -1;
+/// Matching the first character of a word is worth more.
+const BONUS_BOUNDARY: i32 = SCORE_MATCH / 2;
+const BONUS_BOUNDARY_WHITE: i32 = BONUS_BOUNDARY + 2;
+const BONUS_BOUNDARY_DELIMITER: i32 = BONUS_BOUNDARY + 1;
+const BONUS_NON_WORD: i32 = SCORE_MATCH / 2;
+/// eg. the `B` in `runBin` or the `2` in `build2`.
+const BONUS_CAMEL_123: i32 = BONUS_BOUNDARY + SCORE_GAP_EXTENSION;
+/// Makes up for the gap a consecutive match doesn't have.
+const BONUS_CONSECUTIVE: i32 = -(SCORE_GAP_START + SCORE_GAP_EXTENSION);
+const BONUS_FIRST_CHAR_MULTIPLIER: i32 = 2;
+
+/// Marks an impossible match, low enough to stay below any real score after gap penalties.
+const NONE: i32 = i32::MIN / 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CharClass {
+  White,
+  Delimiter,
+  NonWord,
+  Lower,
+  Upper,
+  Number,
+}
+
+fn char_class(c: char) -> CharClass {
+  if c.is_lowercase() {
+    CharClass::Lower
+  } else if c.is_uppercase() {
+    CharClass::Upper
+  } else if c.is_numeric() {
+    CharClass::Number
+  } else if c.is_whitespace() {
+    CharClass::White
+  } else if matches!(c, '/' | ',' | ':' | ';' | '|' | '-' | '_' | '.') {
+    CharClass::Delimiter
+  } else if c.is_alphabetic() {
+    CharClass::Lower
+  } else {
+    CharClass::NonWord
+  }
+}
+
+fn bonus(prev: CharClass, class: CharClass) -> i32 {
+  use CharClass::*;
+  match (prev, class) {
+    (White, Lower | Upper | Number) => BONUS_BOUNDARY_WHITE,
+    (Delimiter, Lower | Upper | Number) => BONUS_BOUNDARY_DELIMITER,
+    (NonWord, Lower | Upper | Number) => BONUS_BOUNDARY,
+    (Lower, Upper) | (Lower | Upper, Number) => BONUS_CAMEL_123,
+    (_, White) => BONUS_BOUNDARY_WHITE,
+    (_, Delimiter | NonWord) => BONUS_NON_WORD,
+    _ => 0,
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+  /// Higher is better.
+  pub score: i32,
+  /// The indices of the matched chars in the text.
+  pub positions: Vec<usize>,
+}
+
+/// Matches each whitespace separated term of the query in the text,
+/// with the characters of a term appearing in order but not necessarily next to each other.
+/// Case insensitive, unless the query has an uppercase letter.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<FuzzyMatch> {
+  let case_sensitive = query.chars().any(char::is_uppercase);
+  let mut total = FuzzyMatch {
+    score: 0,
+    positions: Vec::new(),
+  };
+  for term in query.split_whitespace() {
+    let found = match_term(term, text, case_sensitive)?;
+    total.score += found.score;
+    total.positions.extend(found.positions);
+  }
+  total.positions.sort_unstable();
+  total.positions.dedup();
+  Some(total)
+}
+
+/// Smith-Waterman style: the best scoring alignment of the term in the text,
+/// rewarding consecutive matches and matches at word boundaries, and penalizing gaps.
+fn match_term(term: &str, text: &str, case_sensitive: bool) -> Option<FuzzyMatch> {
+  let fold = |c: char| {
+    if case_sensitive {
+      c
+    } else {
+      c.to_lowercase().next().unwrap_or(c)
+    }
+  };
+  let term = term.chars().map(fold).collect::<Vec<_>>();
+  let chars = text.chars().collect::<Vec<_>>();
+  let folded = chars.iter().map(|&c| fold(c)).collect::<Vec<_>>();
+  let mut prev = CharClass::White;
+  let bonuses = chars
+    .iter()
+    .map(|&c| {
+      let class = char_class(c);
+      let bonus = bonus(prev, class);
+      prev = class;
+      bonus
+    })
+    .collect::<Vec<_>>();
+
+  let (m, n) = (term.len(), chars.len());
+  // The best score with term[i] matched at text[j].
+  let mut scores = vec![vec![NONE; n]; m];
+  // The length of the run of consecutive matches ending at text[j].
+  let mut runs = vec![vec![0; n]; m];
+  // Where term[i - 1] was matched, to trace back the positions.
+  let mut from = vec![vec![0; n]; m];
+  for i in 0..m {
+    // The best way to get here with a gap after term[i - 1].
+    let mut gap = NONE;
+    let mut gap_from = 0;
+    for j in 0..n {
+      if i > 0 && j >= 2 {
+        gap += SCORE_GAP_EXTENSION;
+        let start = scores[i - 1][j - 2] + SCORE_GAP_START;
+        if start > gap {
+          gap = start;
+          gap_from = j - 2;
+        }
+      }
+      if folded[j] != term[i] {
+        continue;
+      }
+      if i == 0 {
+        scores[i][j] = SCORE_MATCH + bonuses[j] * BONUS_FIRST_CHAR_MULTIPLIER;
+        runs[i][j] = 1;
+        continue;
+      }
+      if j == 0 {
+        continue;
+      }
+      if scores[i - 1][j - 1] > NONE / 2 {
+        let run = runs[i - 1][j - 1] + 1;
+        // A run keeps the bonus of its first character.
+        let bonus = bonuses[j].max(bonuses[j + 1 - run]).max(BONUS_CONSECUTIVE);
+        scores[i][j] = scores[i - 1][j - 1] + SCORE_MATCH + bonus;
+        runs[i][j] = run;
+        from[i][j] = j - 1;
+      }
+      if gap > NONE / 2 {
+        let score = gap + SCORE_MATCH + bonuses[j];
+        if score > scores[i][j] {
+          scores[i][j] = score;
+          runs[i][j] = 1;
+          from[i][j] = gap_from;
+        }
+      }
+    }
+  }
+
+  let last = scores.last()?;
+  let (mut j, &score) = last
+    .iter()
+    .enumerate()
+    .filter(|(_, &score)| score > NONE / 2)
+    .max_by_key(|(j, &score)| (score, std::cmp::Reverse(*j)))?;
+  let mut positions = vec![0; m];
+  for i in (0..m).rev() {
+    positions[i] = j;
+    j = from[i][j];
+  }
+  Some(FuzzyMatch { score, positions })
+}
+
+#[cfg(test)]
+mod fuzzy_tests {
+  use super::fuzzy_match;
+
+  fn score(query: &str, text: &str) -> i32 {
+    fuzzy_match(query, text).map_or(i32::MIN, |found| found.score)
+  }
+
+  #[test]
+  fn match_positions_and_ranking() {
+    let found = fuzzy_match("rb", "runnables-bin").unwrap();
+    assert_eq!(found.positions, [0, 10]);
+    // Smart case.
+    assert!(fuzzy_match("RUN", "runnables").is_none());
+    assert_eq!(fuzzy_match("RUN", "RUN-all").unwrap().positions, [0, 1, 2]);
+    // Every term has to match.
+    assert!(fuzzy_match("run xyz", "runnables").is_none());
+    assert_eq!(
+      fuzzy_match("run bin", "runnables-bin").unwrap().positions,
+      [0, 1, 2, 10, 11, 12]
+    );
+    // Consecutive and word boundary matches rank higher.
+    assert!(score("test", "test-all") > score("test", "the-est-suite"));
+    assert!(score("db", "deploy-backend") > score("db", "adobe"));
+    assert!(score("cli", "runnables-cli") > score("cli", "clean-install"));
+  }
+}
@@ -0,0 +1,233 @@
+use std::{collections::HashMap, rc::Rc};
+
+use anyhow::{anyhow, Context};
+use derive_variants::ExtractVariant;
+
+use crate::types::{Runnable, RunnableParams, RunnableParamsVariant};
+
+/// A prerequisite to run, in a list ordered so each step comes after its dependencies.
+#[derive(Debug)]
+pub struct Step {
+  pub runnable: Rc<Runnable>,
+  /// The indices of the earlier steps this one runs after.
+  pub after: Vec<usize>,
+}
+
+/// The `after` dependencies between all the runnables,
+/// resolved up front so problems are found before anything runs.
+pub struct RunnableGraph<'a> {
+  runnables: &'a [Rc<Runnable>],
+  /// The dependencies of each runnable, by index,
+  /// or why they couldn't be resolved.
+  edges: Vec<Result<Vec<usize>, String>>,
+  /// Lookup of the first runnable with the type and name.
+  index: HashMap<(RunnableParamsVariant, &'a str), usize>,
+}
+
+impl<'a> RunnableGraph<'a> {
+  pub fn new(runnables: &'a [Rc<Runnable>]) -> RunnableGraph<'a> {
+    let mut index = HashMap::new();
+    for (i, runnable) in runnables.iter().enumerate() {
+      index
+        .entry((runnable.params.extract_variant(), runnable.name.as_str()))
+        .or_insert(i);
+    }
+    let mut graph = RunnableGraph {
+      runnables,
+      edges: Vec::new(),
+      index,
+    };
+    graph.edges = runnables
+      .iter()
+      .map(|runnable| graph.resolve(runnable))
+      .collect();
+    graph
+  }
+
+  /// The runnables to run before `runnable`, each once, in the order they need to run.
+  pub fn prerequisites(&self, runnable: &Runnable) -> anyhow::Result<Vec<Step>> {
+    let mut order = Vec::new();
+    let mut path = Vec::new();
+    // By index, as several runnables can share a name, eg. runfile items in different directories.
+    let listed = self.runnables.get(runnable.index).filter(|listed| {
+      listed.name == runnable.name
+        && listed.params.extract_variant() == runnable.params.extract_variant()
+    });
+    let dependencies = match listed.map(|_| runnable.index) {
+      // Start from the listed runnable so cycles back to it are caught.
+      Some(root) => {
+        path.push(root);
+        self.edges[root].clone()
+      }
+      None => self.resolve(runnable),
+    }
+    .map_err(|e| anyhow!(e))?;
+    for dependency in dependencies {
+      self.visit(dependency, &mut path, &mut order)?;
+    }
+    let steps = order
+      .iter()
+      .map(|&node| Step {
+        runnable: self.runnables[node].clone(),
+        after: self.edges[node]
+          .iter()
+          .flatten()
+          .filter_map(|dependency| order.iter().position(|i| i == dependency))
+          .collect(),
+      })
+      .collect();
+    Ok(steps)
+  }
+
+  /// Depth first, pushing each runnable after its dependencies.
+  fn visit(
+    &self,
+    node: usize,
+    path: &mut Vec<usize>,
+    order: &mut Vec<usize>,
+  ) -> anyhow::Result<()> {
+    if order.contains(&node) {
+      return Ok(());
+    }
+    if let Some(start) = path.iter().position(|&i| i == node) {
+      let cycle = path[start..]
+        .iter()
+        .chain([&node])
+        .map(|&i| self.runnables[i].name.as_str())
+        .collect::<Vec<_>>()
+        .join(" -> ");
+      return Err(anyhow!("cycle in 'after': {cycle}"));
+    }
+    let dependencies = self.edges[node].as_ref().map_err(|e| anyhow!("{e}"))?;
+    path.push(node);
+    for &dependency in dependencies {
+      self.visit(dependency, path, order)?;
+    }
+    path.pop();
+    order.push(node);
+    Ok(())
+  }
+
+  fn find(&self, variant: RunnableParamsVariant, name: &str) -> Option<usize> {
+    self.index.get(&(variant, name)).copied()
+  }
+
+  fn resolve(&self, runnable: &Runnable) -> Result<Vec<usize>, String> {
+    // Task runs the deps itself, so they are only informational.
+    if let RunnableParams::Taskfile(_) = runnable.params {
+      return Ok(Vec::new());
+    }
+    let Some(after) = &runnable.after else {
+      return Ok(Vec::new());
+    };
+    after
+      .iter()
+      .map(|after| {
+        let (variant, name) = parse_reference(after)
+          .with_context(|| format!("in 'after' of '{}'", runnable.name))
+          .map_err(|e| format!("{e:#}"))?;
+        self.find(variant, name).ok_or_else(|| {
+          format!(
+            "'{}' runs after '{after}', which was not found",
+            runnable.name
+          )
+        })
+      })
+      .collect()
+  }
+}
+
+/// Parses `Type:name`, the type defaulting to `RunFile`.
+fn parse_reference(after: &str) -> anyhow::Result<(RunnableParamsVariant, &str)> {
+  match after.split_once(':') {
+    Some((variant, name)) => {
+      let variant = variant
+        .parse::<RunnableParamsVariant>()
+        .map_err(|_| anyhow!("unknown runnable type '{variant}' in '{after}'"))?;
+      Ok((variant, name))
+    }
+    None => Ok((RunnableParamsVariant::RunFile, after)),
+  }
+}
+
+#[cfg(test)]
+mod graph_tests {
+  use std::rc::Rc;
+
+  use super::{RunnableGraph, Step};
+  use crate::types::{Runnable, RunnableParams};
+
+  fn runnable(name: &str, after: &[&str]) -> Rc<Runnable> {
+    indexed(0, name, after)
+  }
+
+  fn indexed(index: usize, name: &str, after: &[&str]) -> Rc<Runnable> {
+    Rc::new(Runnable {
+      index,
+      name: name.to_string(),
+      after: Some(after.iter().map(|after| after.to_string()).collect()),
+      params: RunnableParams::RunFile(Default::default()),
+      ..Default::default()
+    })
+  }
+
+  fn names(steps: &[Step]) -> Vec<String> {
+    steps
+      .iter()
+      .map(|step| step.runnable.name.clone())
+      .collect()
+  }
+
+  #[test]
+  fn diamond_runs_each_once() {
+    let runnables = [
+      runnable("a", &["b", "c"]),
+      runnable("b", &["d"]),
+      runnable("c", &["RunFile:d"]),
+      runnable("d", &[]),
+    ];
+    let graph = RunnableGraph::new(&runnables);
+    let steps = graph.prerequisites(&runnables[0]).unwrap();
+    assert_eq!(names(&steps), ["d", "b", "c"]);
+    // b and c only wait on d, so they can run in parallel.
+    assert_eq!(steps[0].after, Vec::<usize>::new());
+    assert_eq!(steps[1].after, [0]);
+    assert_eq!(steps[2].after, [0]);
+  }
+
+  #[test]
+  fn cycles_and_missing_references_are_errors() {
+    let runnables = [
+      runnable("a", &["b"]),
+      runnable("b", &["a"]),
+      runnable("c", &["missing"]),
+      runnable("d", &["Bogus:a"]),
+    ];
+    let graph = RunnableGraph::new(&runnables);
+    let error = graph.prerequisites(&runnables[0]).unwrap_err();
+    assert_eq!(error.to_string(), "cycle in 'after': a -> b -> a");
+    let error = graph.prerequisites(&runnables[2]).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "'c' runs after 'missing', which was not found"
+    );
+    let error = graph.prerequisites(&runnables[3]).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "in 'after' of 'd': unknown runnable type 'Bogus' in 'Bogus:a'"
+    );
+  }
+
+  #[test]
+  fn runnables_sharing_a_name_keep_their_own_chain() {
+    let runnables = [
+      indexed(0, "build", &["a"]),
+      indexed(1, "build", &["b"]),
+      indexed(2, "a", &[]),
+      indexed(3, "b", &[]),
+    ];
+    let graph = RunnableGraph::new(&runnables);
+    assert_eq!(names(&graph.prerequisites(&runnables[0]).unwrap()), ["a"]);
+    assert_eq!(names(&graph.prerequisites(&runnables[1]).unwrap()), ["b"]);
+  }
+}
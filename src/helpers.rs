@@ -37,14 +37,122 @@ pub fn wait_for_enter() -> anyhow::Result<()> {
   Ok(())
 }
 
-pub fn split_match_strings(search: &str, target: &str, extras: &[&str]) -> bool {
-  if search.split(' ').all(|term| target.contains(term)) {
-    return true;
+/// Quotes the args for `sh`, leaving plain words as they are.
+pub fn shell_join(args: &[String]) -> String {
+  args
+    .iter()
+    .map(|arg| shell_quote(arg))
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Quotes the arg for `sh`, unless it is a plain word.
+pub fn shell_quote(arg: &str) -> String {
+  let plain = !arg.is_empty()
+    && arg
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || "-_./=:,@+%".contains(c));
+  if plain {
+    arg.to_string()
+  } else {
+    format!("'{}'", arg.replace('\'', r"'\''"))
   }
-  for extra in extras {
-    if search.split(' ').all(|term| extra.contains(term)) {
-      return true;
+}
+
+/// The text without ANSI escape sequences, eg. to search in colored output.
+pub fn strip_ansi(line: &str) -> String {
+  let mut plain = String::with_capacity(line.len());
+  let mut chars = line.chars();
+  while let Some(c) = chars.next() {
+    if c != '\x1b' {
+      plain.push(c);
+      continue;
+    }
+    match chars.next() {
+      // CSI, eg. colors: ends with a letter or one of @[\]^_`{|}~
+      Some('[') => {
+        for c in chars.by_ref() {
+          if ('@'..='~').contains(&c) {
+            break;
+          }
+        }
+      }
+      // OSC, eg. hyperlinks: ends with BEL or ESC \
+      Some(']') => {
+        while let Some(c) = chars.next() {
+          if c == '\x07' || c == '\x1b' && chars.next() == Some('\\') {
+            break;
+          }
+        }
+      }
+      _ => {}
     }
   }
-  false
+  plain
+}
+
+/// A directory under the system temp dir for tests, removed with its contents when dropped,
+/// even if the test panics.
+#[cfg(test)]
+pub struct TestDir(PathBuf);
+
+#[cfg(test)]
+impl TestDir {
+  /// Creates an empty directory, `name` keeps it apart from the ones of other tests.
+  pub fn new(name: &str) -> TestDir {
+    let path = env::temp_dir().join(format!("run-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    std::fs::create_dir_all(&path).unwrap();
+    TestDir(path)
+  }
+
+  /// Writes the file at `path`, relative to the directory, creating its parent directories.
+  pub fn write(&self, path: &str, contents: &str) -> PathBuf {
+    let path = self.0.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, contents).unwrap();
+    path
+  }
+}
+
+#[cfg(test)]
+impl std::ops::Deref for TestDir {
+  type Target = Path;
+
+  fn deref(&self) -> &Path {
+    &self.0
+  }
+}
+
+#[cfg(test)]
+impl Drop for TestDir {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_dir_all(&self.0);
+  }
+}
+
+#[cfg(test)]
+mod helpers_tests {
+  use super::{shell_join, strip_ansi};
+
+  #[test]
+  fn shell_join_quotes_args() {
+    let args = ["--port", "8080", "two words", "it's", "", "$HOME"].map(String::from);
+    assert_eq!(
+      shell_join(&args),
+      r"--port 8080 'two words' 'it'\''s' '' '$HOME'"
+    );
+  }
+
+  #[test]
+  fn strip_ansi_colors_and_links() {
+    assert_eq!(
+      strip_ansi("\x1b[1m\x1b[32m   Compiling\x1b[0m app"),
+      "   Compiling app"
+    );
+    assert_eq!(
+      strip_ansi("see \x1b]8;;https://example.com\x1b\\docs\x1b]8;;\x07!"),
+      "see docs!"
+    );
+  }
 }
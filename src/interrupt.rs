@@ -0,0 +1,235 @@
+use std::{
+  io,
+  os::unix::process::{CommandExt, ExitStatusExt},
+  process::{Child, Command, ExitStatus},
+  sync::{Mutex, OnceLock},
+  thread,
+  time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use libc::{SIGHUP, SIGINT, SIGKILL, SIGTERM};
+use signal_hook::iterator::Signals;
+
+use crate::runnables::failed_status;
+
+/// The runs attached to the terminal, which signals sent to `run` are forwarded to.
+pub static TERMINAL: ProcessGroups = ProcessGroups::new();
+
+static GRACE_PERIOD: OnceLock<Duration> = OnceLock::new();
+
+/// Catches SIGINT, SIGTERM and SIGHUP sent to `run` and forwards them to the
+/// [TERMINAL] process groups. Only the first call does anything.
+pub fn forward_signals() -> anyhow::Result<()> {
+  static FORWARDING: OnceLock<()> = OnceLock::new();
+  if FORWARDING.set(()).is_err() {
+    return Ok(());
+  }
+  let mut signals =
+    Signals::new([SIGINT, SIGTERM, SIGHUP]).context("failed to listen for signals")?;
+  thread::spawn(move || {
+    for signal in signals.forever() {
+      TERMINAL.interrupt(signal);
+    }
+  });
+  Ok(())
+}
+
+/// Sets how long interrupted runs get to exit before they are killed. Default: 5 seconds
+pub fn set_grace_period(grace_period: Duration) {
+  GRACE_PERIOD.set(grace_period).ok();
+}
+
+/// The running children of a run, by the pid of their process group leader,
+/// so they can be stopped along with anything they started.
+pub struct ProcessGroups {
+  groups: Mutex<Vec<i32>>,
+  /// When the run was interrupted.
+  interrupted_at: Mutex<Option<Instant>>,
+}
+
+impl ProcessGroups {
+  pub const fn new() -> ProcessGroups {
+    ProcessGroups {
+      groups: Mutex::new(Vec::new()),
+      interrupted_at: Mutex::new(None),
+    }
+  }
+
+  /// Whether the run was interrupted, so nothing else should be started.
+  pub fn interrupted(&self) -> bool {
+    self.interrupted_at.lock().unwrap().is_some()
+  }
+
+  /// Clears the interruption before starting another run.
+  pub fn reset(&self) {
+    *self.interrupted_at.lock().unwrap() = None;
+  }
+
+  /// Sends the signal to every group.
+  /// Whatever is still running after the grace period is killed by [ProcessGroups::wait].
+  pub fn interrupt(&self, signal: i32) {
+    self.mark_interrupted();
+    for &group in self.groups.lock().unwrap().iter() {
+      kill_group(group, signal);
+    }
+  }
+
+  /// Spawns the process as the leader of a new process group,
+  /// so signals also reach anything it starts, like the binary under `cargo run`.
+  ///
+  /// When `run` owns the terminal, it is handed to the new group for the lifetime of the child,
+  /// so Ctrl-C goes straight to it and it can still read from stdin.
+  ///
+  /// Once the run is interrupted, nothing more is spawned and an [io::ErrorKind::Interrupted]
+  /// error is returned, so a step starting as the run is stopped doesn't outlive it.
+  pub fn spawn(&self, process: &mut Command, terminal: bool) -> io::Result<Child> {
+    process.process_group(0);
+    if terminal && owns_terminal() {
+      // Safety: only calls async signal safe functions between fork and exec.
+      unsafe {
+        process.pre_exec(|| {
+          without_sigttou(|| libc::tcsetpgrp(libc::STDIN_FILENO, libc::getpid()));
+          Ok(())
+        });
+      }
+    }
+    // Checked while holding the groups, so an interrupt either comes first and stops the spawn,
+    // or waits for the group to be added and signals it.
+    let mut groups = self.groups.lock().unwrap();
+    if self.interrupted() {
+      return Err(io::Error::new(
+        io::ErrorKind::Interrupted,
+        "the run was interrupted",
+      ));
+    }
+    let child = process.spawn()?;
+    groups.push(child.id() as i32);
+    Ok(child)
+  }
+
+  /// Waits for a child made with [ProcessGroups::spawn] and takes back the terminal.
+  /// If the run was interrupted, the group is killed once the grace period is up,
+  /// including anything left over after the child itself exited.
+  pub fn wait(&self, child: &mut Child) -> ExitStatus {
+    let group = child.id() as i32;
+    let status = loop {
+      match child.try_wait() {
+        Ok(Some(status)) => break status,
+        Ok(None) => {}
+        Err(_) => break failed_status(),
+      }
+      if self.grace_period_over() {
+        kill_group(group, SIGKILL);
+      }
+      thread::sleep(Duration::from_millis(20));
+    };
+    unsafe {
+      if libc::isatty(libc::STDIN_FILENO) == 1 && libc::tcgetpgrp(libc::STDIN_FILENO) == group {
+        without_sigttou(|| libc::tcsetpgrp(libc::STDIN_FILENO, libc::getpgrp()));
+      }
+    }
+    self.groups.lock().unwrap().retain(|&g| g != group);
+    // With the terminal handed over, Ctrl-C only reaches the child.
+    // An exit code of 130 alone doesn't count: commands can exit with it on their own,
+    // it only means interrupted when `run` got or forwarded the signal itself.
+    if matches!(status.signal(), Some(SIGINT | SIGTERM)) {
+      self.mark_interrupted();
+    }
+    if self.interrupted() {
+      while group_alive(group) && !self.grace_period_over() {
+        thread::sleep(Duration::from_millis(50));
+      }
+      if group_alive(group) {
+        kill_group(group, SIGKILL);
+      }
+    }
+    status
+  }
+
+  fn mark_interrupted(&self) {
+    self
+      .interrupted_at
+      .lock()
+      .unwrap()
+      .get_or_insert_with(Instant::now);
+  }
+
+  fn grace_period_over(&self) -> bool {
+    self
+      .interrupted_at
+      .lock()
+      .unwrap()
+      .is_some_and(|interrupted_at| interrupted_at.elapsed() >= grace_period())
+  }
+}
+
+fn grace_period() -> Duration {
+  GRACE_PERIOD
+    .get()
+    .copied()
+    .unwrap_or(Duration::from_secs(5))
+}
+
+fn owns_terminal() -> bool {
+  unsafe {
+    libc::isatty(libc::STDIN_FILENO) == 1 && libc::tcgetpgrp(libc::STDIN_FILENO) == libc::getpgrp()
+  }
+}
+
+/// Changing the foreground group from the background raises SIGTTOU unless it is blocked.
+unsafe fn without_sigttou(f: impl FnOnce() -> libc::c_int) {
+  let mut block = std::mem::zeroed::<libc::sigset_t>();
+  let mut previous = std::mem::zeroed::<libc::sigset_t>();
+  libc::sigemptyset(&mut block);
+  libc::sigaddset(&mut block, libc::SIGTTOU);
+  libc::pthread_sigmask(libc::SIG_BLOCK, &block, &mut previous);
+  f();
+  libc::pthread_sigmask(libc::SIG_SETMASK, &previous, std::ptr::null_mut());
+}
+
+fn kill_group(group: i32, signal: i32) {
+  unsafe {
+    libc::kill(-group, signal);
+  }
+}
+
+/// Whether any process is left in the group.
+fn group_alive(group: i32) -> bool {
+  unsafe { libc::kill(-group, 0) == 0 }
+}
+
+#[cfg(test)]
+mod interrupt_tests {
+  use std::process::Command;
+
+  use super::ProcessGroups;
+
+  #[test]
+  fn exit_code_130_alone_is_not_an_interruption() {
+    let groups = ProcessGroups::new();
+    let mut child = groups
+      .spawn(Command::new("sh").args(["-c", "exit 130"]), false)
+      .unwrap();
+    let status = groups.wait(&mut child);
+    assert_eq!(status.code(), Some(130));
+    assert!(!groups.interrupted());
+
+    let mut child = groups
+      .spawn(Command::new("sh").args(["-c", "kill -INT $$"]), false)
+      .unwrap();
+    groups.wait(&mut child);
+    assert!(groups.interrupted());
+  }
+
+  #[test]
+  fn nothing_is_spawned_once_interrupted() {
+    let groups = ProcessGroups::new();
+    groups.interrupt(libc::SIGTERM);
+    let error = groups.spawn(&mut Command::new("true"), false).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::Interrupted);
+    groups.reset();
+    let mut child = groups.spawn(&mut Command::new("true"), false).unwrap();
+    assert!(groups.wait(&mut child).success());
+  }
+}
@@ -0,0 +1,195 @@
+use std::{fmt::Display, path::PathBuf, process::Command};
+
+use indexmap::IndexMap;
+
+use crate::helpers::shell_quote;
+
+/// A planned process to spawn: the program, its argv, where to run it and the extra environment.
+/// Only invocations made with [Invocation::shell] go through a shell.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Invocation {
+  pub program: String,
+  pub args: Vec<String>,
+  /// The working directory, or the current one when None.
+  pub dir: Option<PathBuf>,
+  pub env: IndexMap<String, String>,
+  /// How extra args reach the script in the last arg, for invocations made with [Invocation::shell].
+  pub script: Option<ScriptArgs>,
+}
+
+/// How extra args are passed to a script run with `<shell> -c <script>`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScriptArgs {
+  /// POSIX shells: as positional params after `$0`, used by a `"$@"` appended to the script.
+  Positional,
+  /// Other interpreters, eg. `python3` or `fish`: as plain args after the script,
+  /// read by the script itself, eg. from `sys.argv`.
+  Argv,
+}
+
+/// Shells which take `-c <script> <$0> <args>...`.
+const POSIX_SHELLS: [&str; 7] = ["sh", "bash", "dash", "zsh", "ksh", "mksh", "ash"];
+
+/// The name of the program in a shell setting, eg. `python3` for `/usr/bin/env python3 -u`.
+pub fn shell_name(shell: &str) -> &str {
+  shell
+    .split_whitespace()
+    .filter(|word| !word.starts_with('-'))
+    .map(|word| word.rsplit('/').next().unwrap_or(word))
+    .find(|name| *name != "env")
+    .unwrap_or_default()
+}
+
+pub fn is_posix_shell(shell: &str) -> bool {
+  POSIX_SHELLS.contains(&shell_name(shell))
+}
+
+impl Invocation {
+  pub fn new(program: impl Into<String>) -> Invocation {
+    Invocation {
+      program: program.into(),
+      ..Default::default()
+    }
+  }
+
+  /// Splits a command like `cargo build --release` or `/usr/bin/env bash` on whitespace
+  /// into the program and args. For program names and flags, not for shell strings.
+  pub fn words(command: &str) -> Invocation {
+    let mut words = command.split_whitespace();
+    let program = words.next().unwrap_or_default();
+    Invocation::new(program).args(words)
+  }
+
+  /// Runs the `script` with `<shell> -c <script>`.
+  /// The shell can include flags, eg. `bash -eu`, or go through env, eg. `/usr/bin/env python3`.
+  pub fn shell(shell: &str, script: impl Into<String>) -> Invocation {
+    let mut invocation = Invocation::words(shell).arg("-c").arg(script);
+    invocation.script = Some(if is_posix_shell(shell) {
+      ScriptArgs::Positional
+    } else {
+      ScriptArgs::Argv
+    });
+    invocation
+  }
+
+  pub fn arg(mut self, arg: impl Into<String>) -> Invocation {
+    self.args.push(arg.into());
+    self
+  }
+
+  pub fn args<I, S>(mut self, args: I) -> Invocation
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.args.extend(args.into_iter().map(Into::into));
+    self
+  }
+
+  pub fn dir(mut self, dir: impl Into<PathBuf>) -> Invocation {
+    self.dir = Some(dir.into());
+    self
+  }
+
+  pub fn envs(mut self, env: &IndexMap<String, String>) -> Invocation {
+    self.env.extend(env.clone());
+    self
+  }
+
+  /// Appends extra args, after a `--` if `separate`.
+  /// POSIX shell scripts get them as positional params, used by a `"$@"` appended to the script
+  /// after its trailing newlines, so they stay on the script's last line.
+  pub fn append_args(&mut self, args: &[String], separate: bool) {
+    if args.is_empty() {
+      return;
+    }
+    if self.script == Some(ScriptArgs::Positional) {
+      if let Some(script) = self.args.last_mut() {
+        script.truncate(script.trim_end().len());
+        script.push_str(r#" "$@""#);
+      }
+      // $0
+      self.args.push(self.program.clone());
+    }
+    if separate {
+      self.args.push(String::from("--"));
+    }
+    self.args.extend(args.iter().cloned());
+  }
+
+  pub fn command(&self) -> Command {
+    let mut command = Command::new(&self.program);
+    command.args(&self.args).envs(&self.env);
+    if let Some(dir) = &self.dir {
+      command.current_dir(dir);
+    }
+    command
+  }
+}
+
+/// The command line as it could be typed into a shell, without the directory or environment.
+impl Display for Invocation {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(&shell_quote(&self.program))?;
+    for arg in &self.args {
+      write!(f, " {}", shell_quote(arg))?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod invocation_tests {
+  use super::{Invocation, ScriptArgs};
+
+  #[test]
+  fn append_args_to_argv_and_scripts() {
+    let mut cargo = Invocation::words("cargo run --release").dir("/tmp/my project");
+    cargo.append_args(&[String::from("--port"), String::from("80 80")], true);
+    assert_eq!(cargo.program, "cargo");
+    assert_eq!(cargo.args, ["run", "--release", "--", "--port", "80 80"]);
+    assert_eq!(cargo.to_string(), "cargo run --release -- --port '80 80'");
+
+    let mut script = Invocation::shell("bash -eu", "echo $HOME");
+    script.append_args(&[String::from("it's")], false);
+    assert_eq!(script.program, "bash");
+    assert_eq!(
+      script.args,
+      ["-eu", "-c", r#"echo $HOME "$@""#, "bash", "it's"]
+    );
+  }
+
+  #[test]
+  fn append_args_to_multi_line_script() {
+    let mut script = Invocation::shell("sh", "echo one\necho two\n\n");
+    script.append_args(&[String::from("--flag"), String::from("a b")], false);
+    let output = script.command().output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+      String::from_utf8_lossy(&output.stdout),
+      "one\ntwo --flag a b\n"
+    );
+  }
+
+  #[test]
+  fn append_args_to_interpreter_argv() {
+    let mut python = Invocation::shell("python3", "import sys; print(sys.argv)");
+    python.append_args(&[String::from("x"), String::from("it's")], false);
+    assert_eq!(python.script, Some(ScriptArgs::Argv));
+    assert_eq!(
+      python.args,
+      ["-c", "import sys; print(sys.argv)", "x", "it's"]
+    );
+    let output = python.command().output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+      String::from_utf8_lossy(&output.stdout),
+      "['-c', 'x', \"it's\"]\n"
+    );
+
+    let env_bash = Invocation::shell("/usr/bin/env bash -eu", "echo");
+    assert_eq!(env_bash.script, Some(ScriptArgs::Positional));
+    let fish = Invocation::shell("/usr/bin/fish", "echo $argv");
+    assert_eq!(fish.script, Some(ScriptArgs::Argv));
+  }
+}
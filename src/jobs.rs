@@ -0,0 +1,216 @@
+use std::{
+  collections::VecDeque,
+  rc::Rc,
+  sync::{Arc, Mutex, MutexGuard},
+  thread,
+  time::{Duration, Instant},
+};
+
+use colored::Colorize;
+use libc::SIGTERM;
+
+use crate::{
+  graph::Step, interrupt::ProcessGroups, runnables::Output, sources::run_runnable, types::Runnable,
+};
+
+/// How a job is doing, shown next to its runnable in the list.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JobStatus {
+  Running,
+  Succeeded,
+  Failed(i32),
+  Killed,
+}
+
+/// Everything needed to start a runnable in the background, and to restart it.
+#[derive(Clone)]
+pub struct JobPlan {
+  pub runnable: Runnable,
+  /// The `after` chain, each with the indices of the steps it runs after.
+  pub prerequisites: Vec<(Runnable, Vec<usize>)>,
+  pub jobs: usize,
+  pub args: Vec<String>,
+}
+
+impl JobPlan {
+  pub fn new(runnable: Runnable, prerequisites: &[Step], jobs: usize, args: &[String]) -> JobPlan {
+    JobPlan {
+      runnable,
+      prerequisites: prerequisites
+        .iter()
+        .map(|step| (step.runnable.as_ref().clone(), step.after.clone()))
+        .collect(),
+      jobs,
+      args: args.to_vec(),
+    }
+  }
+}
+
+/// How many lines of output a job keeps, older lines are dropped
+/// so something printing forever doesn't fill up the memory.
+pub const MAX_LINES: usize = 10_000;
+
+/// The latest output of a job. Lines are numbered from the start of the output,
+/// so positions in it stay put as older lines are dropped.
+#[derive(Default)]
+pub struct JobLines {
+  lines: VecDeque<String>,
+  /// How many lines were dropped from the front.
+  dropped: usize,
+}
+
+impl JobLines {
+  /// The number of the oldest line kept.
+  pub fn first(&self) -> usize {
+    self.dropped
+  }
+
+  /// The number after the last line.
+  pub fn end(&self) -> usize {
+    self.dropped + self.lines.len()
+  }
+
+  pub fn get(&self, line: usize) -> Option<&String> {
+    self.lines.get(line.checked_sub(self.dropped)?)
+  }
+
+  /// The lines kept from the line numbered `from`.
+  pub fn from(&self, from: usize) -> impl Iterator<Item = &String> {
+    self.lines.iter().skip(from.saturating_sub(self.dropped))
+  }
+
+  fn push(&mut self, line: String) {
+    if self.lines.len() == MAX_LINES {
+      self.lines.pop_front();
+      self.dropped += 1;
+    }
+    self.lines.push_back(line);
+  }
+}
+
+/// A runnable started from the interface, running in the background
+/// with its output collected for the output pane.
+pub struct Job {
+  pub plan: JobPlan,
+  pub groups: ProcessGroups,
+  lines: Mutex<JobLines>,
+  status: Mutex<JobStatus>,
+  started: Mutex<Instant>,
+  finished: Mutex<Option<Duration>>,
+}
+
+impl Job {
+  /// Runs the plan on a new thread.
+  pub fn start(plan: JobPlan) -> Arc<Job> {
+    Job::start_after(plan, None)
+  }
+
+  /// Runs the plan once the `previous` job has stopped, eg. to restart it.
+  pub fn start_after(plan: JobPlan, previous: Option<Arc<Job>>) -> Arc<Job> {
+    let job = Arc::new(Job::new(plan, JobStatus::Running));
+    let thread_job = job.clone();
+    thread::spawn(move || {
+      if let Some(previous) = previous {
+        while previous.status() == JobStatus::Running {
+          thread::sleep(Duration::from_millis(20));
+        }
+      }
+      *thread_job.started.lock().unwrap() = Instant::now();
+      thread_job.run();
+    });
+    job
+  }
+
+  /// A job which couldn't be started, eg. because of a cycle in `after`.
+  pub fn failed(plan: JobPlan, error: &anyhow::Error) -> Arc<Job> {
+    let job = Job::new(plan, JobStatus::Failed(1));
+    job.push_line(format!("{error:#}").red().to_string());
+    *job.finished.lock().unwrap() = Some(Duration::ZERO);
+    Arc::new(job)
+  }
+
+  fn new(plan: JobPlan, status: JobStatus) -> Job {
+    Job {
+      plan,
+      groups: ProcessGroups::new(),
+      lines: Default::default(),
+      status: Mutex::new(status),
+      started: Mutex::new(Instant::now()),
+      finished: Default::default(),
+    }
+  }
+
+  fn run(&self) {
+    let JobPlan {
+      runnable,
+      prerequisites,
+      jobs,
+      args,
+    } = &self.plan;
+    let prerequisites = prerequisites
+      .iter()
+      .map(|(runnable, after)| Step {
+        runnable: Rc::new(runnable.clone()),
+        after: after.clone(),
+      })
+      .collect::<Vec<_>>();
+    let output = Output {
+      prefix: None,
+      job: Some(self),
+    };
+    let outcome = run_runnable(runnable, &prerequisites, *jobs, args, output);
+    let elapsed = self.started.lock().unwrap().elapsed();
+    let status = if outcome.interrupted {
+      JobStatus::Killed
+    } else if outcome.status.success() {
+      JobStatus::Succeeded
+    } else {
+      JobStatus::Failed(outcome.status.code().unwrap_or(1))
+    };
+    let summary = match status {
+      JobStatus::Running | JobStatus::Succeeded => "FINISHED in".dimmed(),
+      JobStatus::Failed(code) => format!("FAILED (code {code}) in").red().bold(),
+      JobStatus::Killed => "KILLED after".yellow().bold(),
+    };
+    self.push_line(String::new());
+    self.push_line(format!("{summary} {}", format!("{elapsed:.2?}").bold()));
+    if let Some(broken_by) = outcome.broken_by {
+      self.push_line(format!(
+        "{} {}",
+        "chain stopped by:".dimmed(),
+        broken_by.bold()
+      ));
+    }
+    *self.finished.lock().unwrap() = Some(elapsed);
+    *self.status.lock().unwrap() = status;
+  }
+
+  pub fn status(&self) -> JobStatus {
+    *self.status.lock().unwrap()
+  }
+
+  /// How long it has been running, or ran for.
+  pub fn elapsed(&self) -> Duration {
+    self
+      .finished
+      .lock()
+      .unwrap()
+      .unwrap_or_else(|| self.started.lock().unwrap().elapsed())
+  }
+
+  /// Stops the run, the processes get the grace period to exit before they are killed.
+  pub fn kill(&self) {
+    if self.status() == JobStatus::Running {
+      self.groups.interrupt(SIGTERM);
+    }
+  }
+
+  /// The latest output, one entry per line, with ANSI colors.
+  pub fn lines(&self) -> MutexGuard<'_, JobLines> {
+    self.lines.lock().unwrap()
+  }
+
+  pub fn push_line(&self, line: String) {
+    self.lines.lock().unwrap().push(line);
+  }
+}
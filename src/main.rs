@@ -1,4 +1,4 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use anyhow::anyhow;
 use clap::Parser;
@@ -8,10 +8,27 @@ use helpers::wait_for_enter;
 use ratatui::style::Color;
 use types::RunnableParamsVariant;
 
-use crate::{sources::run_runnable, types::RunnableParams};
+use crate::{
+  config::config,
+  runnables::{Output, RunOutcome},
+  sources::run_runnable,
+  state::LastRun,
+  types::RunnableParams,
+};
 
+// Process groups and signal forwarding rely on Unix APIs.
+#[cfg(not(unix))]
+compile_error!("runnables-cli only supports Unix-like systems");
+
+mod config;
 mod deserializers;
+mod dotenv;
+mod fuzzy;
+mod graph;
 mod helpers;
+mod interrupt;
+mod invocation;
+mod jobs;
 mod runnables;
 mod sources;
 mod state;
@@ -23,11 +40,11 @@ mod types;
 pub struct CliArgs {
   /// Specify a specific runnable to run.
   /// Can also match on aliases configured in the runfile.
-  /// 
+  ///
   /// Example:
-  /// 
+  ///
   /// - "just-ls" or "RunFile:just-ls" - match to a runnable declared in runfile.
-  /// 
+  ///
   /// - "RustBin:runnables-cli" - match to a runnable picked up as a rust binary crate.
   runnable: Option<String>,
   /// The root path to search for runnables.
@@ -43,10 +60,47 @@ pub struct CliArgs {
   /// Ignore specific runnable types
   #[arg(long, short = 'i')]
   ignore: Vec<RunnableParamsVariant>,
+  /// Discover rust runnables using `cargo metadata`,
+  /// running them from the workspace root with `-p <package>`.
+  /// Falls back to reading the Cargo.toml when cargo is unavailable.
+  #[arg(long, short = 'm')]
+  cargo_metadata: bool,
+  /// Open a picker for the crate's features and profile
+  /// before running rust runnables. Toggle in the interface with 'o'.
+  #[arg(long, short = 'o')]
+  cargo_options: bool,
+  /// The number of prerequisites in the `after` chain to run at the same time.
+  /// Default: all of them for runfile items with `parallel = true`, otherwise 1.
+  #[arg(long, short = 'j')]
+  jobs: Option<usize>,
+  /// Set a param declared by the runfile item or a just recipe parameter, as name=value. Can be repeated.
+  #[arg(long = "param", short = 'P', value_parser = parse_param)]
+  params: Vec<(String, String)>,
+  /// Come back to the interface after the runnable exits, keeping the search and selection.
+  /// Toggle in the interface with 'S'.
+  #[arg(long, short = 'S')]
+  stay: bool,
+  /// Run runnables inside the interface, each in a tab of the output pane,
+  /// so several can run at once. Toggle in the interface with 'E'.
+  #[arg(long, short = 'E')]
+  embed: bool,
+  /// Extra arguments after `--`, appended to the command of the runnable.
+  ///
+  /// Example: run my-task -- --verbose --port 8080
+  #[arg(last = true)]
+  args: Vec<String>,
+}
+
+fn parse_param(param: &str) -> Result<(String, String), String> {
+  param
+    .split_once('=')
+    .map(|(name, value)| (name.to_string(), value.to_string()))
+    .ok_or_else(|| format!("expected name=value, got '{param}'"))
 }
 
 fn main() -> anyhow::Result<()> {
   let mut state = state::State::new()?;
+  interrupt::set_grace_period(config().grace_period());
 
   if state.runnables.is_empty() {
     println!("no runnables found 🧐");
@@ -54,7 +108,7 @@ fn main() -> anyhow::Result<()> {
     return Ok(());
   }
 
-  if let Some(label) = state.args.runnable {
+  if let Some(label) = state.args.runnable.take() {
     let (runnable_type, label) = match label.split_once(':') {
       Some((runnable_type, label)) => (runnable_type, label),
       None => ("RunFile", label.as_str()),
@@ -72,26 +126,103 @@ fn main() -> anyhow::Result<()> {
       return Err(anyhow!("runnable not found: {}", label));
     };
     state.runnable = runnable;
-  } else {
+    let timer = Instant::now();
+    let outcome = run_selected(&mut state)?;
+    return exit(&outcome, timer.elapsed());
+  }
+
+  loop {
     // Open the interface to select runnable.
     if let Err(e) = tui::run(&mut state) {
       println!("\n{e:#?}");
       return Ok(());
     }
+
+    if let RunnableParams::None = state.runnable.params {
+      // no selection was made
+      state.output.stop();
+      return Ok(());
+    }
+    let timer = Instant::now();
+    if !state.stay {
+      let outcome = run_selected(&mut state);
+      state.output.stop();
+      return exit(&outcome?, timer.elapsed());
+    }
+    let outcome = run_selected(&mut state).map_err(|e| format!("{e:#}"));
+    let elapsed = timer.elapsed();
+    match &outcome {
+      Ok(outcome) => {
+        report(outcome, elapsed);
+      }
+      Err(e) => println!("\n{}", e.red()),
+    }
+    state.last_run = Some(LastRun {
+      name: std::mem::take(&mut state.runnable).name,
+      outcome,
+      elapsed,
+    });
   }
+}
 
-  if let RunnableParams::None = state.runnable.params {
-    // no selection was made
-    return Ok(());
+/// Runs the chosen runnable after its `after` chain.
+fn run_selected(state: &mut state::State) -> anyhow::Result<RunOutcome> {
+  let (prerequisites, jobs) = state.plan()?;
+  state.runnable.log_info(&state.args.args);
+  interrupt::forward_signals()?;
+  interrupt::TERMINAL.reset();
+  Ok(run_runnable(
+    &state.runnable,
+    &prerequisites,
+    jobs,
+    &state.args.args,
+    Output::TERMINAL,
+  ))
+}
+
+/// Prints how the run went, returning the exit code for it.
+fn report(outcome: &RunOutcome, elapsed: Duration) -> i32 {
+  let RunOutcome {
+    status,
+    broken_by,
+    interrupted,
+  } = outcome;
+  let elapsed = format!("{elapsed:.2?}").bold();
+  if *interrupted {
+    println!("\n{} {elapsed}", "INTERRUPTED after".yellow().bold());
+    if let Some(broken_by) = broken_by {
+      println!(
+        "{} {}",
+        "chain stopped at:".dimmed(),
+        broken_by.yellow().bold()
+      );
+    }
+    // The conventional exit code for Ctrl-C.
+    return 130;
   }
-  state.runnable.log_info();
-  let timer = Instant::now();
-  run_runnable(&state.runnable, &state.runnables);
+  if status.success() {
+    println!("\n{} {elapsed}", "FINISHED in".dimmed());
+    return 0;
+  }
+  // Killed by a signal when there is no code.
+  let code = status.code().unwrap_or(1);
   println!(
-    "\n{} {}",
-    "FINISHED in".dimmed(),
-    format!("{:.2?}", timer.elapsed()).bold()
+    "\n{} {elapsed}",
+    format!("FAILED (code {code}) in").red().bold()
   );
+  if let Some(broken_by) = broken_by {
+    println!(
+      "{} {}",
+      "chain stopped by:".dimmed(),
+      broken_by.red().bold()
+    );
+  }
+  code
+}
 
-  Ok(())
+fn exit(outcome: &RunOutcome, elapsed: Duration) -> anyhow::Result<()> {
+  match report(outcome, elapsed) {
+    0 => Ok(()),
+    code => std::process::exit(code),
+  }
 }
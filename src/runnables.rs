@@ -1,15 +1,17 @@
 use std::{
   fs,
+  io::{self, BufRead, BufReader},
+  os::unix::process::ExitStatusExt,
   path::{Path, PathBuf},
-  rc::Rc,
+  process::{Command, ExitStatus, Stdio},
+  thread,
 };
 
-use derive_variants::ExtractVariant;
-use run_command::run_command_pipe_to_terminal;
-
 use crate::{
-  sources::run_runnable,
-  types::{Runnable, RunnableParamsVariant},
+  interrupt::{self, ProcessGroups},
+  invocation::Invocation,
+  jobs::Job,
+  types::Runnable,
 };
 
 pub fn ignore_dir(path: &Path) -> bool {
@@ -26,55 +28,223 @@ pub trait AddRunnables {
     runignores: &[PathBuf],
     runnables: &mut Vec<Runnable>,
   ) {
-    Self::add_runnable(path, runnables).ok();
-    let Ok(entries) = fs::read_dir(path) else {
-      return;
-    };
-    for entry in entries.flatten() {
-      if let Ok(metadata) = entry.metadata() {
-        if metadata.is_dir() {
-          let path = entry.path();
-          // Unwrap ok, path definitely on the system
-          let norm = path.canonicalize().unwrap();
-          if (runincludes.is_empty() || runincludes.contains(&norm))
-            && !ignore_dir(&path)
-            && !runignores.contains(&norm)
-          {
-            Self::add_runnables(&path, runincludes, runignores, runnables);
-          }
+    walk(path, runincludes, runignores, &mut |path| {
+      Self::add_runnable(path, runnables).ok();
+    });
+  }
+}
+
+/// Visits `path` and every directory below it,
+/// skipping the ignored directories and the ones left out by the runincludes.
+pub fn walk(
+  path: &Path,
+  runincludes: &[PathBuf],
+  runignores: &[PathBuf],
+  visit: &mut impl FnMut(&Path),
+) {
+  visit(path);
+  let Ok(entries) = fs::read_dir(path) else {
+    return;
+  };
+  for entry in entries.flatten() {
+    if let Ok(metadata) = entry.metadata() {
+      if metadata.is_dir() {
+        let path = entry.path();
+        // Unwrap ok, path definitely on the system
+        let norm = path.canonicalize().unwrap();
+        if walks_into(&path, &norm, runincludes, runignores) {
+          walk(&path, runincludes, runignores, visit);
         }
       }
     }
   }
 }
 
+/// Whether [walk] goes into the directory at `path`, canonicalized as `norm`.
+fn walks_into(path: &Path, norm: &Path, runincludes: &[PathBuf], runignores: &[PathBuf]) -> bool {
+  (runincludes.is_empty() || runincludes.iter().any(|include| include == norm))
+    && !ignore_dir(path)
+    && !runignores.iter().any(|ignore| ignore == norm)
+}
+
+/// Whether [walk] from `root` visits `dir`.
+pub fn walk_reaches(
+  root: &Path,
+  dir: &Path,
+  runincludes: &[PathBuf],
+  runignores: &[PathBuf],
+) -> bool {
+  let (Ok(mut path), Ok(dir)) = (root.canonicalize(), dir.canonicalize()) else {
+    return false;
+  };
+  let Ok(relative) = dir.strip_prefix(&path) else {
+    return false;
+  };
+  relative.components().all(|component| {
+    path.push(component);
+    walks_into(&path, &path, runincludes, runignores)
+  })
+}
+
 pub trait RunRunnable {
   type Params;
 
-  fn command(runnable: &Runnable, params: &Self::Params) -> String;
-
-  fn run(runnable: &Runnable, params: &Self::Params, runnables: &[Rc<Runnable>]) {
-    if let Some(after) = &runnable.after {
-      for after in after {
-        let (runnable_type, after) = after
-          .split_once(':')
-          .map(|(ty, after)| {
-            (
-              ty.parse::<RunnableParamsVariant>()
-                .expect("Invalid runnable variant in 'after'"),
-              after,
-            )
-          })
-          .unwrap_or((RunnableParamsVariant::RunFile, after));
-        if let Some(before) = runnables
-          .iter()
-          .find(|r| r.params.extract_variant() == runnable_type && r.name == *after)
-        {
-          run_runnable(before, runnables);
+  /// The process to spawn for the runnable.
+  fn invocation(runnable: &Runnable, params: &Self::Params) -> Invocation;
+
+  /// Whether extra args need a `--` in front to reach the program,
+  /// eg. `cargo run -- <args>`.
+  fn separate_args(_: &Self::Params) -> bool {
+    false
+  }
+
+  /// Runs the invocation with the extra `args` appended, returning its exit status.
+  /// The `after` chain is run beforehand by [run_runnable](crate::sources::run_runnable).
+  fn run(
+    runnable: &Runnable,
+    params: &Self::Params,
+    args: &[String],
+    output: Output,
+  ) -> ExitStatus {
+    let mut invocation = Self::invocation(runnable, params);
+    invocation.append_args(args, Self::separate_args(params));
+    let process = invocation.command();
+    if output.is_terminal() {
+      run_process(process)
+    } else {
+      run_process_piped(process, output)
+    }
+  }
+}
+
+/// Where the output of a run goes.
+#[derive(Clone, Copy)]
+pub struct Output<'a> {
+  /// Written in front of each line, so runnables running at the same time can be told apart.
+  pub prefix: Option<&'a str>,
+  /// Collects the output for the output pane, instead of writing it to the terminal.
+  pub job: Option<&'a Job>,
+}
+
+impl<'a> Output<'a> {
+  /// Straight to the terminal, with stdin attached.
+  pub const TERMINAL: Output<'static> = Output {
+    prefix: None,
+    job: None,
+  };
+
+  pub fn prefixed(self, prefix: &'a str) -> Output<'a> {
+    Output {
+      prefix: Some(prefix),
+      ..self
+    }
+  }
+
+  fn is_terminal(&self) -> bool {
+    self.prefix.is_none() && self.job.is_none()
+  }
+
+  /// The process groups the run is spawned in, to stop them together.
+  pub fn groups(&self) -> &'a ProcessGroups {
+    match self.job {
+      Some(job) => &job.groups,
+      None => &interrupt::TERMINAL,
+    }
+  }
+
+  fn line(&self, line: &str, stderr: bool) {
+    let line = match self.prefix {
+      Some(prefix) => format!("{prefix} {line}"),
+      None => line.to_string(),
+    };
+    match self.job {
+      Some(job) => job.push_line(line),
+      None if stderr => eprintln!("{line}"),
+      None => println!("{line}"),
+    }
+  }
+}
+
+/// The status of a process which exited with code 1, for when it couldn't be spawned or waited on.
+pub fn failed_status() -> ExitStatus {
+  // A raw wait status, which has the exit code in its second byte.
+  ExitStatus::from_raw(1 << 8)
+}
+
+fn run_process(mut process: Command) -> ExitStatus {
+  let groups = &interrupt::TERMINAL;
+  match groups.spawn(&mut process, true) {
+    Ok(mut child) => groups.wait(&mut child),
+    // Stopped before it started, the run reports the interruption.
+    Err(e) if e.kind() == io::ErrorKind::Interrupted => failed_status(),
+    Err(e) => {
+      eprintln!("failed to spawn: {e}");
+      failed_status()
+    }
+  }
+}
+
+/// Runs the process with the output piped line by line, and stdin closed.
+fn run_process_piped(mut process: Command, output: Output) -> ExitStatus {
+  process
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped());
+  if output.job.is_some() {
+    // Most tools turn colors off when not writing to a terminal,
+    // but the output pane can show them.
+    process.envs([
+      ("CLICOLOR_FORCE", "1"),
+      ("FORCE_COLOR", "1"),
+      ("CARGO_TERM_COLOR", "always"),
+    ]);
+  }
+  let groups = output.groups();
+  let mut child = match groups.spawn(&mut process, false) {
+    Ok(child) => child,
+    Err(e) if e.kind() == io::ErrorKind::Interrupted => return failed_status(),
+    Err(e) => {
+      output.line(&format!("failed to spawn: {e}"), true);
+      return failed_status();
+    }
+  };
+  let stdout = child.stdout.take();
+  let stderr = child.stderr.take();
+  thread::scope(|scope| {
+    if let Some(stdout) = stdout {
+      scope.spawn(|| {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+          output.line(&line, false);
         }
-      }
+      });
+    }
+    if let Some(stderr) = stderr {
+      scope.spawn(|| {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+          output.line(&line, true);
+        }
+      });
+    }
+    // Waits before the output is drained, as killed stragglers may hold the pipes open.
+    groups.wait(&mut child)
+  })
+}
+
+/// The result of running a runnable along with its prerequisites.
+pub struct RunOutcome {
+  pub status: ExitStatus,
+  /// The prerequisite which failed and stopped the chain.
+  pub broken_by: Option<String>,
+  /// Whether the run was stopped by Ctrl-C, a signal or the kill key.
+  pub interrupted: bool,
+}
+
+impl From<ExitStatus> for RunOutcome {
+  fn from(status: ExitStatus) -> Self {
+    RunOutcome {
+      status,
+      broken_by: None,
+      interrupted: false,
     }
-    let command = Self::command(runnable, params);
-    run_command_pipe_to_terminal(&command);
   }
 }
@@ -0,0 +1,204 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+};
+
+use anyhow::anyhow;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{
+  invocation::Invocation,
+  runnables::{AddRunnables, RunRunnable},
+  types::{Runnable, RunnableParams},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct CargoAliasParams {
+  /// What the alias expands to, eg. `run --package xtask --`.
+  pub expansion: String,
+  /// Whether this is the `cargo xtask` alias of an xtask crate.
+  pub xtask: bool,
+  /// The config file declaring the alias.
+  pub config: PathBuf,
+}
+
+/// Config files in the order cargo prefers them:
+/// when both exist, cargo reads the extensionless one (with a warning).
+const CARGO_CONFIGS: &[&str] = &[".cargo/config", ".cargo/config.toml"];
+
+#[derive(Deserialize)]
+struct CargoConfig {
+  #[serde(default)]
+  alias: IndexMap<String, toml::Value>,
+}
+
+pub struct CargoAlias;
+
+impl AddRunnables for CargoAlias {
+  fn add_runnable(path: &Path, runnables: &mut Vec<Runnable>) -> anyhow::Result<()> {
+    if !path.join("Cargo.toml").is_file() {
+      return Err(anyhow!("directory does not include Cargo.toml"));
+    }
+    // Like cargo, pick up the config in the crate directory and in every parent directory,
+    // the nearest one winning when several declare the same alias.
+    let mut seen = Vec::new();
+    for dir in path.canonicalize()?.ancestors() {
+      let Some((config, contents)) = CARGO_CONFIGS.iter().find_map(|config| {
+        let config = dir.join(config);
+        fs::read_to_string(&config)
+          .ok()
+          .map(|contents| (config, contents))
+      }) else {
+        continue;
+      };
+      let Ok(CargoConfig { alias }) = toml::from_str(&contents) else {
+        continue;
+      };
+      for (name, value) in alias {
+        if seen.contains(&name) {
+          continue;
+        }
+        seen.push(name.clone());
+        // Crates below one already scanned share its config, the alias is listed once.
+        let listed = runnables.iter().any(|runnable| {
+          matches!(&runnable.params, RunnableParams::CargoAlias(params) if params.config == config)
+            && runnable.name == name
+        });
+        if listed {
+          continue;
+        }
+        let expansion = match value {
+          toml::Value::String(expansion) => expansion,
+          toml::Value::Array(args) => args
+            .iter()
+            .filter_map(toml::Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" "),
+          _ => continue,
+        };
+        let xtask_dir = dir.join("xtask");
+        let xtask = name == "xtask" && xtask_dir.join("Cargo.toml").is_file();
+        if xtask {
+          // The alias is the way to run it, so the binary itself isn't listed.
+          runnables.retain(|runnable| !is_xtask_bin(runnable, &xtask_dir));
+        }
+        runnables.push(Runnable {
+          name,
+          description: None,
+          aliases: Default::default(),
+          display_name: None,
+          after: None,
+          path: path.to_owned(),
+          index: 0,
+          params: RunnableParams::CargoAlias(CargoAliasParams {
+            expansion,
+            xtask,
+            config: config.clone(),
+          }),
+        });
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Whether the runnable is the binary of the xtask crate in `xtask_dir`.
+fn is_xtask_bin(runnable: &Runnable, xtask_dir: &Path) -> bool {
+  let RunnableParams::RustBin(params) = &runnable.params else {
+    return false;
+  };
+  let crate_dir = match &params.package {
+    Some(package) => package.dir(&runnable.path),
+    None => runnable.path.clone(),
+  };
+  runnable.name == "xtask" && crate_dir.canonicalize().is_ok_and(|dir| dir == xtask_dir)
+}
+
+impl RunRunnable for CargoAlias {
+  type Params = CargoAliasParams;
+
+  fn invocation(runnable: &Runnable, _: &Self::Params) -> Invocation {
+    Invocation::new("cargo")
+      .arg(&runnable.name)
+      .dir(&runnable.path)
+  }
+}
+
+#[cfg(test)]
+mod cargo_alias_tests {
+  use super::CargoAlias;
+  use crate::{
+    helpers::TestDir,
+    runnables::AddRunnables,
+    sources::rust_bin::RustBin,
+    types::{Runnable, RunnableParams},
+  };
+
+  #[test]
+  fn nearest_config_wins_and_xtask_bin_is_hidden() {
+    let root = TestDir::new("cargo-alias");
+    root.write(
+      ".cargo/config.toml",
+      "[alias]\nxtask = \"run --package xtask --\"\nci = \"xtask ci\"\n",
+    );
+    root.write(
+      "crates/member/.cargo/config.toml",
+      "[alias]\nci = \"test\"\n",
+    );
+    root.write("Cargo.toml", "[workspace]\n");
+    root.write("crates/member/Cargo.toml", "[package]\nname = \"member\"\n");
+    root.write("xtask/Cargo.toml", "[package]\nname = \"xtask\"\n");
+    root.write("xtask/src/main.rs", "fn main() {}\n");
+
+    let mut runnables = Vec::new();
+    RustBin::add_runnables(&root, &[], &[], &mut runnables);
+    assert_eq!(runnables.len(), 1);
+    CargoAlias::add_runnables(&root, &[], &[], &mut runnables);
+
+    let aliases = runnables
+      .iter()
+      .map(|runnable: &Runnable| {
+        let RunnableParams::CargoAlias(params) = &runnable.params else {
+          panic!("expected only aliases, got {}", runnable.name);
+        };
+        let dir = runnable.path.strip_prefix(&*root).unwrap();
+        (
+          runnable.name.as_str(),
+          params.expansion.as_str(),
+          dir.to_owned(),
+        )
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(
+      aliases,
+      [
+        ("ci", "xtask ci", "".into()),
+        ("xtask", "run --package xtask --", "".into()),
+        ("ci", "test", "crates/member".into()),
+      ]
+    );
+  }
+
+  #[test]
+  fn extensionless_config_wins_over_config_toml() {
+    let root = TestDir::new("cargo-alias-config");
+    root.write("Cargo.toml", "[package]\nname = \"app\"\n");
+    root.write(".cargo/config", "[alias]\nci = \"test\"\n");
+    root.write(
+      ".cargo/config.toml",
+      "[alias]\nci = \"check\"\nlint = \"clippy\"\n",
+    );
+
+    let mut runnables = Vec::new();
+    CargoAlias::add_runnable(&root, &mut runnables).unwrap();
+    let aliases = runnables
+      .iter()
+      .map(|runnable| match &runnable.params {
+        RunnableParams::CargoAlias(params) => (runnable.name.as_str(), params.expansion.as_str()),
+        _ => panic!("expected only aliases, got {}", runnable.name),
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(aliases, [("ci", "test")]);
+  }
+}
@@ -0,0 +1,280 @@
+use std::{
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+
+use crate::{
+  runnables::{walk, walk_reaches, AddRunnables},
+  sources::{
+    cargo_toml::{CargoAction, CargoTomlPackageMetadata},
+    rust_bin::{RustBin, RustBinParams, RustBinTarget},
+    rust_lib::{RustLib, RustLibParams},
+  },
+  types::{Runnable, RunnableParams},
+};
+
+/// A package in a cargo workspace, run from the workspace root with `-p <name>`.
+#[derive(Debug, Clone)]
+pub struct CargoPackage {
+  pub name: String,
+  /// The package directory, relative to the workspace root.
+  pub path: PathBuf,
+}
+
+impl CargoPackage {
+  /// The package directory, under the workspace `root`.
+  pub fn dir(&self, root: &Path) -> PathBuf {
+    root.join(&self.path)
+  }
+
+  /// The directory to run from: custom actions always run from the crate directory,
+  /// cargo commands from the workspace `root`.
+  pub fn run_dir(
+    package: &Option<CargoPackage>,
+    action: &Option<CargoAction>,
+    root: &Path,
+  ) -> PathBuf {
+    match (action, package) {
+      (Some(_), Some(package)) => package.dir(root),
+      _ => root.to_owned(),
+    }
+  }
+}
+
+#[derive(Deserialize)]
+struct CargoMetadataOutput {
+  packages: Vec<CargoMetadataPackage>,
+  workspace_root: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct CargoMetadataPackage {
+  name: String,
+  description: Option<String>,
+  manifest_path: PathBuf,
+  targets: Vec<CargoMetadataTarget>,
+  /// `[package.metadata]`, null when not set.
+  metadata: Option<CargoTomlPackageMetadata>,
+}
+
+#[derive(Deserialize)]
+struct CargoMetadataTarget {
+  name: String,
+  kind: Vec<String>,
+  #[serde(default, rename = "required-features")]
+  required_features: Vec<String>,
+}
+
+/// Discovers rust runnables from `cargo metadata` rather than parsing each Cargo.toml.
+/// Falls back to [RustBin] / [RustLib] for any crate cargo can't load.
+pub struct CargoMetadata;
+
+impl AddRunnables for CargoMetadata {
+  /// Walks like the other sources, then drops the packages the walk doesn't reach,
+  /// as cargo lists every package of the workspace, wherever the walk started.
+  fn add_runnables(
+    path: &Path,
+    runincludes: &[PathBuf],
+    runignores: &[PathBuf],
+    runnables: &mut Vec<Runnable>,
+  ) {
+    let start = runnables.len();
+    walk(path, runincludes, runignores, &mut |path| {
+      Self::add_runnable(path, runnables).ok();
+    });
+    let added = runnables.split_off(start);
+    runnables.extend(added.into_iter().filter(|runnable| {
+      let package = match &runnable.params {
+        RunnableParams::RustBin(RustBinParams { package, .. })
+        | RunnableParams::RustLib(RustLibParams { package, .. }) => package,
+        _ => return true,
+      };
+      package.as_ref().is_none_or(|package| {
+        walk_reaches(path, &package.dir(&runnable.path), runincludes, runignores)
+      })
+    }));
+  }
+
+  fn add_runnable(path: &Path, runnables: &mut Vec<Runnable>) -> anyhow::Result<()> {
+    if !path.join("Cargo.toml").is_file() {
+      return Err(anyhow!("directory does not include Cargo.toml"));
+    }
+    // Members are already covered by the workspace root, which is scanned first.
+    let manifest_dir = path.canonicalize()?;
+    let covered = runnables.iter().any(|runnable| {
+      let package = match &runnable.params {
+        RunnableParams::RustBin(RustBinParams { package, .. })
+        | RunnableParams::RustLib(RustLibParams { package, .. }) => package,
+        _ => return false,
+      };
+      package
+        .as_ref()
+        .is_some_and(|package| package.dir(&runnable.path) == manifest_dir)
+    });
+    if covered {
+      return Ok(());
+    }
+
+    match cargo_metadata(path) {
+      Ok(metadata) => {
+        add_metadata_runnables(metadata, runnables);
+        Ok(())
+      }
+      Err(_) => {
+        RustBin::add_runnable(path, runnables).ok();
+        RustLib::add_runnable(path, runnables)
+      }
+    }
+  }
+}
+
+fn cargo_metadata(path: &Path) -> anyhow::Result<CargoMetadataOutput> {
+  let output = Command::new("cargo")
+    .args([
+      "metadata",
+      "--no-deps",
+      "--offline",
+      "--format-version",
+      "1",
+    ])
+    .current_dir(path)
+    .output()
+    .context("failed to run cargo metadata")?;
+  if !output.status.success() {
+    return Err(anyhow!(
+      "cargo metadata failed: {}",
+      String::from_utf8_lossy(&output.stderr)
+    ));
+  }
+  serde_json::from_slice(&output.stdout).context("failed to parse cargo metadata output")
+}
+
+const LIB_KINDS: &[&str] = &["lib", "rlib", "dylib", "cdylib", "staticlib", "proc-macro"];
+
+fn add_metadata_runnables(metadata: CargoMetadataOutput, runnables: &mut Vec<Runnable>) {
+  let CargoMetadataOutput {
+    packages,
+    workspace_root,
+  } = metadata;
+  for package in packages {
+    let package_dir = package
+      .manifest_path
+      .parent()
+      .unwrap_or(&workspace_root)
+      .to_path_buf();
+    let cargo_package = CargoPackage {
+      name: package.name.clone(),
+      path: match package_dir.strip_prefix(&workspace_root) {
+        Ok(relative) if relative.as_os_str().is_empty() => PathBuf::from("."),
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => package_dir.clone(),
+      },
+    };
+    let actions = package
+      .metadata
+      .map(|metadata| metadata.runnables.actions)
+      .unwrap_or_default();
+    for target in package.targets {
+      let kind = target.kind.iter().map(String::as_str).collect::<Vec<_>>();
+      let (name, display_name, description, params) = match kind.as_slice() {
+        ["bin"] => (
+          target.name.clone(),
+          (target.name != package.name).then(|| format!("{} / {}", package.name, target.name)),
+          package.description.clone(),
+          RunnableParams::RustBin(RustBinParams {
+            target: RustBinTarget::Bin,
+            command: Default::default(),
+            package: Some(cargo_package.clone()),
+            required_features: target.required_features,
+            options: Default::default(),
+            actions: actions.clone(),
+            action: None,
+          }),
+        ),
+        [kind @ ("example" | "test" | "bench")] => (
+          target.name.clone(),
+          Some(format!("{} / {}", package.name, target.name)),
+          None,
+          RunnableParams::RustBin(RustBinParams {
+            target: match *kind {
+              "example" => RustBinTarget::Example,
+              "test" => RustBinTarget::Test,
+              _ => RustBinTarget::Bench,
+            },
+            command: Default::default(),
+            package: Some(cargo_package.clone()),
+            required_features: target.required_features,
+            options: Default::default(),
+            actions: actions.clone(),
+            action: None,
+          }),
+        ),
+        kinds if kinds.iter().any(|kind| LIB_KINDS.contains(kind)) => (
+          package.name.clone(),
+          None,
+          package.description.clone(),
+          RunnableParams::RustLib(RustLibParams {
+            command: Default::default(),
+            package: Some(cargo_package.clone()),
+            proc_macro: kind.contains(&"proc-macro"),
+            options: Default::default(),
+            actions: actions.clone(),
+            action: None,
+          }),
+        ),
+        // eg. `custom-build` for build scripts
+        _ => continue,
+      };
+      runnables.push(Runnable {
+        name,
+        aliases: Default::default(),
+        display_name,
+        description,
+        after: None,
+        path: workspace_root.clone(),
+        index: 0,
+        params,
+      });
+    }
+  }
+}
+
+#[cfg(test)]
+mod cargo_metadata_tests {
+  use super::CargoMetadata;
+  use crate::{helpers::TestDir, runnables::AddRunnables};
+
+  #[test]
+  fn list_only_the_packages_the_walk_reaches() {
+    let root = TestDir::new("cargo-metadata");
+    root.write(
+      "Cargo.toml",
+      "[workspace]\nmembers = [\"app\", \"tool\"]\nresolver = \"2\"\n",
+    );
+    for member in ["app", "tool"] {
+      root.write(
+        &format!("{member}/Cargo.toml"),
+        &format!("[package]\nname = \"{member}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n"),
+      );
+      root.write(&format!("{member}/src/main.rs"), "fn main() {}\n");
+    }
+    let names = |path: &std::path::Path, runignores: &[std::path::PathBuf]| {
+      let mut runnables = Vec::new();
+      CargoMetadata::add_runnables(path, &[], runignores, &mut runnables);
+      runnables
+        .into_iter()
+        .map(|runnable| runnable.name)
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(names(&root, &[]), ["app", "tool"]);
+    // `tool` is in the root .runignore.
+    let runignores = [root.join("tool").canonicalize().unwrap()];
+    assert_eq!(names(&root, &runignores), ["app"]);
+    // Cargo lists the whole workspace, but the sibling is outside the scanned member.
+    assert_eq!(names(&root.join("app"), &[]), ["app"]);
+  }
+}
@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use crate::sources::cargo_toml::CargoToml;
+
+/// Extra flags picked for a rust runnable before running it.
+#[derive(Debug, Clone, Default)]
+pub struct CargoOptions {
+  pub features: Vec<String>,
+  pub no_default_features: bool,
+  pub profile: Option<String>,
+}
+
+impl CargoOptions {
+  /// The flags to append to the cargo command line.
+  pub fn args(&self) -> Vec<String> {
+    let mut args = Vec::new();
+    if !self.features.is_empty() {
+      args.push(String::from("--features"));
+      args.push(self.features.join(","));
+    }
+    if self.no_default_features {
+      args.push(String::from("--no-default-features"));
+    }
+    if let Some(profile) = &self.profile {
+      args.push(String::from("--profile"));
+      args.push(profile.clone());
+    }
+    args
+  }
+}
+
+/// The features and profiles available to the crate in the directory.
+pub struct CargoOptionsAvailable {
+  /// The `[features]`, excluding `default`.
+  pub features: Vec<String>,
+  /// The built in `dev` / `release` profiles,
+  /// followed by any custom `[profile.*]` declared in the manifest or workspace root.
+  pub profiles: Vec<String>,
+}
+
+impl CargoOptionsAvailable {
+  pub fn read(path: &Path) -> CargoOptionsAvailable {
+    let mut features = Vec::new();
+    let mut profiles = vec![String::from("dev"), String::from("release")];
+    if let Ok(cargo_toml) = CargoToml::read(path) {
+      features.extend(
+        cargo_toml
+          .features
+          .keys()
+          .filter(|feature| *feature != "default")
+          .cloned(),
+      );
+      let root_profiles = cargo_toml
+        .workspace_root(path)
+        .and_then(|(root, _)| CargoToml::read(&root).ok())
+        .map(|root| root.profile)
+        .unwrap_or_default();
+      for profile in cargo_toml
+        .profile
+        .into_keys()
+        .chain(root_profiles.into_keys())
+      {
+        if !profiles.contains(&profile) {
+          profiles.push(profile);
+        }
+      }
+    }
+    CargoOptionsAvailable { features, profiles }
+  }
+}
@@ -0,0 +1,308 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  sync::Mutex,
+};
+
+use anyhow::Context;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::state::State;
+
+/// The parts of a `Cargo.toml` used by the rust sources.
+#[derive(Deserialize)]
+pub struct CargoToml {
+  /// None for virtual workspace manifests.
+  pub package: Option<CargoTomlPackage>,
+  pub workspace: Option<CargoTomlWorkspace>,
+  #[serde(default)]
+  pub bin: Vec<CargoTomlTarget>,
+  #[serde(default)]
+  pub example: Vec<CargoTomlTarget>,
+  #[serde(default)]
+  pub bench: Vec<CargoTomlTarget>,
+  #[serde(default)]
+  pub features: IndexMap<String, Vec<String>>,
+  /// The `[profile.*]` tables. Cargo only applies the ones in the workspace root.
+  #[serde(default)]
+  pub profile: IndexMap<String, toml::Value>,
+}
+
+#[derive(Deserialize)]
+pub struct CargoTomlPackage {
+  pub name: String,
+  pub description: Option<Inheritable<String>>,
+  /// Explicit path to the workspace root, relative to the package.
+  pub workspace: Option<PathBuf>,
+  #[serde(default = "default_auto")]
+  pub autobins: bool,
+  #[serde(default = "default_auto")]
+  pub autoexamples: bool,
+  #[serde(default = "default_auto")]
+  pub autobenches: bool,
+  #[serde(default)]
+  pub metadata: CargoTomlPackageMetadata,
+}
+
+fn default_auto() -> bool {
+  true
+}
+
+impl CargoTomlPackage {
+  /// The package description, if it is set or inherited from the workspace.
+  pub fn description(&self) -> Option<String> {
+    self.description.as_ref()?.value().cloned()
+  }
+}
+
+/// `[package.metadata]`, only the `runnables` table is read, other tools' tables are ignored.
+#[derive(Deserialize, Default)]
+pub struct CargoTomlPackageMetadata {
+  #[serde(default)]
+  pub runnables: CargoTomlRunnables,
+}
+
+/// `[package.metadata.runnables]`
+#[derive(Deserialize, Default)]
+pub struct CargoTomlRunnables {
+  #[serde(default, deserialize_with = "actions_deserializer")]
+  pub actions: Vec<CargoAction>,
+}
+
+/// Drops the actions bound to a [State::RESERVED_KEYS] key, with a warning, as they could never run.
+/// Actions bound to a [State::CARGO_KEYS] key are kept, with a warning that they replace the built-in one.
+fn actions_deserializer<'de, D>(deserializer: D) -> Result<Vec<CargoAction>, D::Error>
+where
+  D: serde::Deserializer<'de>,
+{
+  static WARNED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+  let warn = |warning: String| {
+    // Every Cargo.toml is read by more than one source.
+    let mut warned = WARNED.lock().unwrap();
+    if !warned.contains(&warning) {
+      eprintln!("{warning}");
+      warned.push(warning);
+    }
+  };
+  let mut actions = Vec::<CargoAction>::deserialize(deserializer)?;
+  actions.retain(|action| {
+    if State::CARGO_KEYS.contains(&action.key) {
+      warn(format!(
+        "the '{}' action replaces the built-in action on '{}'",
+        action.label, action.key
+      ));
+    }
+    if !State::RESERVED_KEYS.contains(&action.key) {
+      return true;
+    }
+    warn(format!(
+      "ignoring the '{}' action: '{}' is already used by the interface",
+      action.label, action.key
+    ));
+    false
+  });
+  Ok(actions)
+}
+
+/// A custom action a crate declares for its runnables,
+/// eg. `{ key = "n", label = "nextest", command = "cargo nextest run" }`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CargoAction {
+  pub key: char,
+  pub label: String,
+  /// Run as is from the crate directory.
+  pub command: String,
+}
+
+#[derive(Deserialize)]
+pub struct CargoTomlWorkspace {
+  #[serde(default)]
+  pub members: Vec<String>,
+  #[serde(default)]
+  pub package: CargoTomlWorkspacePackage,
+}
+
+/// The `[workspace.package]` fields members can inherit.
+#[derive(Deserialize, Default)]
+pub struct CargoTomlWorkspacePackage {
+  pub description: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CargoTomlTarget {
+  pub name: String,
+  pub path: Option<PathBuf>,
+}
+
+/// A package field which is either set directly,
+/// or inherited from the workspace with `field.workspace = true`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Inheritable<T> {
+  Value(T),
+  Workspace { workspace: bool },
+}
+
+impl<T> Inheritable<T> {
+  /// The value, or None if it is still waiting to be inherited.
+  pub fn value(&self) -> Option<&T> {
+    match self {
+      Inheritable::Value(value) => Some(value),
+      Inheritable::Workspace { .. } => None,
+    }
+  }
+
+  fn resolve(&mut self, workspace: Option<T>) {
+    if let (Inheritable::Workspace { workspace: true }, Some(value)) = (&self, workspace) {
+      *self = Inheritable::Value(value);
+    }
+  }
+}
+
+impl CargoToml {
+  /// Reads the `Cargo.toml` in the directory,
+  /// resolving any fields inherited from the workspace root.
+  pub fn read(path: &Path) -> anyhow::Result<CargoToml> {
+    let mut cargo_toml = CargoToml::parse(path)?;
+    let inherits = cargo_toml
+      .package
+      .as_ref()
+      .and_then(|package| package.description.as_ref())
+      .is_some_and(|description| description.value().is_none());
+    if inherits {
+      let workspace = cargo_toml.workspace_root(path).map(|(_, root)| root);
+      let package = cargo_toml.package.as_mut().expect("checked above");
+      if let Some(description) = package.description.as_mut() {
+        description.resolve(workspace.and_then(|w| w.package.description));
+      }
+    }
+    Ok(cargo_toml)
+  }
+
+  fn parse(path: &Path) -> anyhow::Result<CargoToml> {
+    let file_path = path.join("Cargo.toml");
+    let contents = fs::read_to_string(&file_path)?;
+    toml::from_str(&contents).with_context(|| format!("failed to parse {}", file_path.display()))
+  }
+
+  /// Finds the workspace this package belongs to,
+  /// either from `package.workspace` or by walking up the parent directories like cargo does.
+  pub fn workspace_root(&self, path: &Path) -> Option<(PathBuf, CargoTomlWorkspace)> {
+    let explicit = self
+      .package
+      .as_ref()
+      .and_then(|package| package.workspace.as_ref());
+    if let Some(root) = explicit {
+      let root = path.join(root);
+      return Some((root.clone(), CargoToml::parse(&root).ok()?.workspace?));
+    }
+    path
+      .canonicalize()
+      .ok()?
+      .ancestors()
+      .find_map(|dir| Some((dir.to_path_buf(), CargoToml::parse(dir).ok()?.workspace?)))
+  }
+}
+
+#[cfg(test)]
+mod cargo_toml_tests {
+  use super::CargoToml;
+  use crate::helpers::TestDir;
+
+  #[test]
+  fn read_inherited_fields_from_the_workspace() {
+    let root = TestDir::new("cargo-toml");
+    root.write(
+      "Cargo.toml",
+      r#"
+[workspace]
+members = ["crates/*"]
+
+[workspace.package]
+version = "1.2.3"
+description = "from the workspace"
+"#,
+    );
+    // Found by walking up the parent directories.
+    root.write(
+      "crates/member/Cargo.toml",
+      r#"
+[package]
+name = "member"
+version.workspace = true
+description.workspace = true
+"#,
+    );
+    // Points at the root explicitly, and overrides the description.
+    root.write(
+      "crates/own/Cargo.toml",
+      r#"
+[package]
+name = "own"
+workspace = "../.."
+version.workspace = true
+description = "its own"
+"#,
+    );
+    let member = CargoToml::read(&root.join("crates/member"));
+    let own = CargoToml::read(&root.join("crates/own"));
+    let workspace_root = own
+      .as_ref()
+      .ok()
+      .and_then(|own| own.workspace_root(&root.join("crates/own")));
+    let virtual_root = CargoToml::read(&root);
+
+    let package = member.unwrap().package.unwrap();
+    assert_eq!(package.name, "member");
+    assert_eq!(package.description().as_deref(), Some("from the workspace"));
+    assert!(package.metadata.runnables.actions.is_empty());
+    let package = own.unwrap().package.unwrap();
+    assert_eq!(package.description().as_deref(), Some("its own"));
+    let (workspace_root, workspace) = workspace_root.unwrap();
+    assert_eq!(workspace_root, root.join("crates/own/../.."));
+    assert_eq!(workspace.members, ["crates/*"]);
+
+    let virtual_root = virtual_root.unwrap();
+    assert!(virtual_root.package.is_none());
+    assert_eq!(
+      virtual_root
+        .workspace
+        .unwrap()
+        .package
+        .description
+        .as_deref(),
+      Some("from the workspace")
+    );
+  }
+
+  #[test]
+  fn parse_custom_actions() {
+    let cargo_toml: CargoToml = toml::from_str(
+      r#"
+[package]
+name = "actions"
+
+[package.metadata.other-tool]
+setting = true
+
+[package.metadata.runnables]
+actions = [
+  { key = "n", label = "nextest", command = "cargo nextest run" },
+  { key = "d", label = "run dev", command = "cargo run -- --config dev.toml" },
+  { key = "q", label = "quick check", command = "cargo check" },
+  { key = "t", label = "test all", command = "cargo test --all-features" },
+]
+"#,
+    )
+    .unwrap();
+    let actions = cargo_toml.package.unwrap().metadata.runnables.actions;
+    // 'q' quits, so the action is dropped.
+    // 't' is the built-in test action, which the custom one replaces.
+    assert_eq!(actions.len(), 3);
+    assert_eq!(actions[0].key, 'n');
+    assert_eq!(actions[0].label, "nextest");
+    assert_eq!(actions[1].command, "cargo run -- --config dev.toml");
+    assert_eq!(actions[2].key, 't');
+  }
+}
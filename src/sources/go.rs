@@ -0,0 +1,162 @@
+use std::{
+  fmt::Display,
+  fs,
+  path::{Component, Path},
+};
+
+use anyhow::{anyhow, Context};
+
+use crate::{
+  invocation::Invocation,
+  runnables::{AddRunnables, RunRunnable},
+  types::{Runnable, RunnableParams},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct GoParams {
+  /// The module path declared in the enclosing `go.mod`.
+  pub module: String,
+  pub command: GoCommand,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub enum GoCommand {
+  #[default]
+  Run,
+  Build,
+  Test,
+  TestRace,
+  Vet,
+  Install,
+}
+
+impl Display for GoCommand {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let d = match self {
+      GoCommand::Run => "go run .",
+      GoCommand::Build => "go build .",
+      GoCommand::Test => "go test .",
+      GoCommand::TestRace => "go test -race .",
+      GoCommand::Vet => "go vet .",
+      GoCommand::Install => "go install .",
+    };
+    f.write_str(d)
+  }
+}
+
+pub struct Go;
+
+impl AddRunnables for Go {
+  fn add_runnable(path: &Path, runnables: &mut Vec<Runnable>) -> anyhow::Result<()> {
+    // The go tool ignores these directories when matching packages.
+    if path.components().any(|component| {
+      matches!(component, Component::Normal(name) if name == "vendor" || name == "testdata")
+    }) {
+      return Err(anyhow!("path is ignored by go"));
+    }
+    if !is_main_package(path)? {
+      return Err(anyhow!("directory is not a main package"));
+    }
+    let canonical = path.canonicalize()?;
+    let (module_dir, module) = canonical
+      .ancestors()
+      .find_map(|dir| Some((dir, read_module_path(dir)?)))
+      .context("main package is not inside a go module")?;
+    let import_path = match canonical.strip_prefix(module_dir) {
+      Ok(relative) if relative.as_os_str().is_empty() => module.clone(),
+      Ok(relative) => format!("{module}/{}", relative.display()),
+      Err(_) => module.clone(),
+    };
+    // `go build` / `go install` name the binary after the last import path element.
+    let name = import_path
+      .rsplit('/')
+      .next()
+      .unwrap_or(&import_path)
+      .to_string();
+    runnables.push(Runnable {
+      name,
+      aliases: Default::default(),
+      display_name: Some(import_path),
+      description: None,
+      after: None,
+      path: path.to_owned(),
+      index: 0,
+      params: RunnableParams::Go(GoParams {
+        module,
+        command: GoCommand::Run,
+      }),
+    });
+    Ok(())
+  }
+}
+
+impl RunRunnable for Go {
+  type Params = GoParams;
+
+  fn invocation(runnable: &Runnable, params: &Self::Params) -> Invocation {
+    Invocation::words(&params.command.to_string()).dir(&runnable.path)
+  }
+}
+
+/// Whether any non test `.go` file in the directory declares `package main`.
+fn is_main_package(path: &Path) -> anyhow::Result<bool> {
+  for entry in fs::read_dir(path)?.flatten() {
+    let file_path = entry.path();
+    let Some(file_name) = file_path.file_name().and_then(|name| name.to_str()) else {
+      continue;
+    };
+    if !file_name.ends_with(".go") || file_name.ends_with("_test.go") {
+      continue;
+    }
+    let Ok(contents) = fs::read_to_string(&file_path) else {
+      continue;
+    };
+    // Files excluded by build constraints can declare another package, so keep looking.
+    if package_name(&contents) == Some("main") {
+      return Ok(true);
+    }
+  }
+  Ok(false)
+}
+
+/// The name in the `package` clause of a Go file.
+fn package_name(contents: &str) -> Option<&str> {
+  contents
+    .lines()
+    .map(str::trim)
+    .find_map(|line| line.strip_prefix("package "))?
+    .split_whitespace()
+    .next()
+}
+
+fn read_module_path(dir: &Path) -> Option<String> {
+  let contents = fs::read_to_string(dir.join("go.mod")).ok()?;
+  contents.lines().find_map(|line| {
+    let module = line.trim().strip_prefix("module")?;
+    let module = module.split("//").next()?.trim().trim_matches('"');
+    (!module.is_empty()).then(|| module.to_string())
+  })
+}
+
+#[cfg(test)]
+mod go_tests {
+  use super::is_main_package;
+  use crate::helpers::TestDir;
+
+  #[test]
+  fn main_package_in_any_non_test_file() {
+    let dir = TestDir::new("go");
+    dir.write("main_test.go", "package main\n");
+    dir.write(
+      "doc.go",
+      "// Package tool does things.\npackage tool_test\n",
+    );
+    assert!(!is_main_package(&dir).unwrap());
+    dir.write("a_windows.go", "//go:build windows\n\npackage other\n");
+    dir.write(
+      "main.go",
+      "// The entry point.\npackage main // import \"example.com/tool\"\n",
+    );
+    assert!(is_main_package(&dir).unwrap());
+  }
+}
@@ -5,7 +5,8 @@ use indexmap::IndexMap;
 use serde::Deserialize;
 
 use crate::{
-  runnables::{FindRunnables, RunRunnable},
+  invocation::Invocation,
+  runnables::{AddRunnables, RunRunnable},
   types::{Runnable, RunnableParams},
 };
 
@@ -19,6 +20,8 @@ pub enum JavascriptCommand {
   #[default]
   Yarn,
   Npm,
+  Pnpm,
+  Bun,
 }
 
 impl Display for JavascriptCommand {
@@ -26,49 +29,71 @@ impl Display for JavascriptCommand {
     let d = match self {
       JavascriptCommand::Yarn => "yarn",
       JavascriptCommand::Npm => "npm run",
+      JavascriptCommand::Pnpm => "pnpm run",
+      JavascriptCommand::Bun => "bun run",
     };
     f.write_str(d)
   }
 }
 
+impl JavascriptCommand {
+  /// Picks the package manager from the lockfile present in the directory.
+  /// Falls back to npm when no lockfile is found.
+  fn from_lockfile(path: &Path) -> JavascriptCommand {
+    const LOCKFILES: &[(&str, JavascriptCommand)] = &[
+      ("yarn.lock", JavascriptCommand::Yarn),
+      ("pnpm-lock.yaml", JavascriptCommand::Pnpm),
+      ("bun.lockb", JavascriptCommand::Bun),
+      ("package-lock.json", JavascriptCommand::Npm),
+    ];
+    LOCKFILES
+      .iter()
+      .find(|(lockfile, _)| path.join(lockfile).is_file())
+      .map(|(_, command)| *command)
+      .unwrap_or(JavascriptCommand::Npm)
+  }
+}
+
 #[derive(Deserialize)]
 struct PackageJson {
-  name: String,
+  name: Option<String>,
+  #[serde(default)]
   scripts: IndexMap<String, String>,
 }
 
 pub struct Javascript;
 
-impl FindRunnables for Javascript {
-  fn find_runnable(path: &Path) -> anyhow::Result<Vec<crate::types::Runnable>> {
+impl AddRunnables for Javascript {
+  fn add_runnable(path: &Path, runnables: &mut Vec<Runnable>) -> anyhow::Result<()> {
     let package_json_contents = fs::read_to_string(path.join("package.json"))
-      .context("directory does not inclue package.json")?;
+      .context("directory does not include package.json")?;
     let PackageJson { name, scripts } =
       serde_json::from_str(&package_json_contents).context("failed to parse package.json")?;
-    let runnables = scripts
-      .into_iter()
-      .map(|(script_name, command)| Runnable {
-        display_name: Some(format!("{name} / {script_name}")),
-        name: script_name,
-        description: Some(command),
-        path: path.to_owned(),
-        index: 0,
-        params: RunnableParams::Javascript(Default::default()),
-      })
-      .collect();
-    Ok(runnables)
+    let command = JavascriptCommand::from_lockfile(path);
+    runnables.extend(scripts.into_iter().map(|(script_name, script)| Runnable {
+      display_name: name.as_ref().map(|name| format!("{name} / {script_name}")),
+      name: script_name,
+      aliases: Default::default(),
+      description: Some(script),
+      after: None,
+      path: path.to_owned(),
+      index: 0,
+      params: RunnableParams::Javascript(JavascriptParams { command }),
+    }));
+    Ok(())
   }
 }
 
 impl RunRunnable for Javascript {
   type Params = JavascriptParams;
 
-  fn command(runnable: &Runnable, params: &Self::Params) -> String {
-    format!(
-      "cd {} && {} {}",
-      runnable.path.display(),
-      params.command,
-      runnable.name
-    )
+  fn invocation(runnable: &Runnable, params: &Self::Params) -> Invocation {
+    Invocation::words(&params.command.to_string())
+      .arg(&runnable.name)
+      .dir(&runnable.path)
+  }
+  /// npm passes args after `--` on to the script.
+  fn separate_args(params: &Self::Params) -> bool {
+    matches!(params.command, JavascriptCommand::Npm)
   }
 }
@@ -0,0 +1,381 @@
+use std::{fs, path::Path};
+
+use anyhow::anyhow;
+use indexmap::IndexMap;
+
+use crate::{
+  invocation::Invocation,
+  runnables::{AddRunnables, RunRunnable},
+  sources::runfile::{RunFileParam, RunFileParamType},
+  types::{Runnable, RunnableParams},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct JustParams {
+  /// The recipe parameters, as written in the justfile
+  /// (eg. `target`, `port="8080"`, `+args`).
+  pub parameters: Vec<String>,
+  /// The values filled in for the [positional](JustParams::positional) parameters, by name.
+  pub values: IndexMap<String, String>,
+}
+
+impl JustParams {
+  /// The parameters to pass a value for, as [RunFileParam]s for the params form:
+  /// the required ones (without a default, and not `*` variadic),
+  /// and the ones before them, as just takes the values in order.
+  /// Defaults written as a quoted string are used as the default value.
+  pub fn positional(&self) -> Vec<RunFileParam> {
+    let count = self
+      .parameters
+      .iter()
+      .rposition(|parameter| !parameter.contains('=') && !parameter.starts_with('*'))
+      .map_or(0, |index| index + 1);
+    self.parameters[..count]
+      .iter()
+      .map(|parameter| {
+        let (parameter, default) = match parameter.split_once('=') {
+          Some((parameter, default)) => (parameter, Some(default.trim())),
+          None => (parameter.as_str(), None),
+        };
+        RunFileParam {
+          name: parameter_name(parameter).to_string(),
+          description: parameter
+            .starts_with('+')
+            .then(|| String::from("one or more, separated by spaces")),
+          default: default
+            .filter(|default| unquote(default) != *default)
+            .map(|default| toml::Value::String(unquote(default).to_string())),
+          choices: Vec::new(),
+          kind: RunFileParamType::String,
+        }
+      })
+      .collect()
+  }
+
+  /// Fills in the value of every [positional](JustParams::positional) parameter,
+  /// from the values already set (eg. in the interface), then the `given` values
+  /// (eg. from `--param`), then the default. Fails if a required one has no value.
+  pub fn resolve(&mut self, name: &str, given: &[(String, String)]) -> anyhow::Result<()> {
+    for parameter in self.positional() {
+      if self.values.contains_key(&parameter.name) {
+        continue;
+      }
+      let value = given
+        .iter()
+        .rev()
+        .find(|(name, _)| *name == parameter.name)
+        .map(|(_, value)| value.clone())
+        .or_else(|| parameter.default_value())
+        .ok_or_else(|| {
+          anyhow!(
+            "missing value for required parameter '{}' of '{name}', pass it with --param {}=<value>",
+            parameter.name,
+            parameter.name
+          )
+        })?;
+      self.values.insert(parameter.name, value);
+    }
+    Ok(())
+  }
+
+  /// The values of the parameters as args, in order.
+  /// Values of `+` variadic parameters are split on whitespace.
+  fn args(&self) -> Vec<String> {
+    let mut args = Vec::new();
+    for parameter in &self.parameters {
+      let parameter = parameter.split('=').next().unwrap_or_default();
+      let Some(value) = self.values.get(parameter_name(parameter)) else {
+        break;
+      };
+      if parameter.starts_with('+') {
+        args.extend(value.split_whitespace().map(str::to_string));
+      } else {
+        args.push(value.clone());
+      }
+    }
+    args
+  }
+}
+
+/// The name of a parameter without its variadic and export markers, eg. `args` for `+$args`.
+fn parameter_name(parameter: &str) -> &str {
+  parameter.trim_start_matches(['+', '*', '$'])
+}
+
+const JUSTFILES: &[&str] = &["justfile", "Justfile", ".justfile"];
+
+pub struct Just;
+
+impl AddRunnables for Just {
+  fn add_runnable(path: &Path, runnables: &mut Vec<Runnable>) -> anyhow::Result<()> {
+    let contents = JUSTFILES
+      .iter()
+      .find_map(|justfile| fs::read_to_string(path.join(justfile)).ok())
+      .ok_or_else(|| anyhow!("directory does not include justfile"))?;
+    runnables.extend(
+      parse_recipes(&contents)
+        .into_iter()
+        .filter(|(_, recipe)| !recipe.private)
+        .map(|(name, recipe)| Runnable {
+          name,
+          aliases: recipe.aliases,
+          display_name: None,
+          description: recipe.doc,
+          after: None,
+          path: path.to_owned(),
+          index: 0,
+          params: RunnableParams::Just(JustParams {
+            parameters: recipe.parameters,
+            values: Default::default(),
+          }),
+        }),
+    );
+    Ok(())
+  }
+}
+
+impl RunRunnable for Just {
+  type Params = JustParams;
+
+  fn invocation(runnable: &Runnable, params: &Self::Params) -> Invocation {
+    Invocation::new("just")
+      .arg(&runnable.name)
+      .args(params.args())
+      .dir(&runnable.path)
+  }
+}
+
+#[derive(Default)]
+struct JustRecipe {
+  doc: Option<String>,
+  parameters: Vec<String>,
+  aliases: Vec<String>,
+  private: bool,
+}
+
+/// Parses the recipes out of a justfile, in declaration order.
+///
+/// The comment line directly above a recipe (or a `[doc('...')]` attribute)
+/// is used as its doc. Recipes marked `[private]` or prefixed with `_` are flagged private.
+fn parse_recipes(contents: &str) -> IndexMap<String, JustRecipe> {
+  let mut recipes = IndexMap::<String, JustRecipe>::new();
+  let mut aliases = Vec::<(String, String)>::new();
+  let mut doc = None::<String>;
+  let mut private = false;
+
+  for line in contents.lines() {
+    // Recipe bodies are indented.
+    if line.starts_with([' ', '\t']) {
+      continue;
+    }
+    let line = line.trim_end();
+    if line.is_empty() {
+      doc = None;
+      private = false;
+      continue;
+    }
+    if let Some(comment) = line.strip_prefix('#') {
+      if !comment.starts_with('!') {
+        doc = Some(comment.trim().to_string());
+      }
+      continue;
+    }
+    if let Some(attributes) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+      for attribute in split_top_level(attributes, ',') {
+        let attribute = attribute.trim();
+        if attribute == "private" {
+          private = true;
+        } else if let Some(value) = attribute
+          .strip_prefix("doc(")
+          .and_then(|a| a.strip_suffix(')'))
+        {
+          doc = Some(unquote(value.trim()).to_string());
+        }
+      }
+      continue;
+    }
+    if let Some(alias) = line.strip_prefix("alias ") {
+      if let Some((alias, recipe)) = alias.split_once(":=") {
+        aliases.push((alias.trim().to_string(), recipe.trim().to_string()));
+      }
+      continue;
+    }
+
+    let Some(header) = recipe_header(line) else {
+      doc = None;
+      private = false;
+      continue;
+    };
+    let mut tokens = split_top_level(header, ' ')
+      .into_iter()
+      .filter(|token| !token.is_empty());
+    let Some(name) = tokens.next() else {
+      continue;
+    };
+    let name = name.trim_start_matches('@');
+    recipes.insert(
+      name.to_string(),
+      JustRecipe {
+        doc: doc.take(),
+        parameters: tokens.map(str::to_string).collect(),
+        aliases: Vec::new(),
+        private: std::mem::take(&mut private) || name.starts_with('_'),
+      },
+    );
+  }
+
+  for (alias, recipe) in aliases {
+    if let Some(recipe) = recipes.get_mut(&recipe) {
+      recipe.aliases.push(alias);
+    }
+  }
+
+  recipes
+}
+
+/// Returns the part of a recipe line before its `:`,
+/// or None if the line is not a recipe (assignment, setting, import...).
+fn recipe_header(line: &str) -> Option<&str> {
+  const KEYWORDS: &[&str] = &["set ", "export ", "import ", "import? ", "mod ", "mod? "];
+  if KEYWORDS.iter().any(|keyword| line.starts_with(keyword)) {
+    return None;
+  }
+  let index = top_level_indices(line, ':').next()?;
+  // `name := value` is an assignment
+  if line[index + 1..].starts_with('=') {
+    return None;
+  }
+  Some(&line[..index])
+}
+
+/// Splits on `separator`, ignoring separators inside quotes and parentheses.
+fn split_top_level(source: &str, separator: char) -> Vec<&str> {
+  let mut parts = Vec::new();
+  let mut start = 0;
+  for index in top_level_indices(source, separator) {
+    parts.push(&source[start..index]);
+    start = index + separator.len_utf8();
+  }
+  parts.push(&source[start..]);
+  parts
+}
+
+fn top_level_indices(source: &str, target: char) -> impl Iterator<Item = usize> + '_ {
+  let mut quote = None::<char>;
+  let mut depth = 0usize;
+  source.char_indices().filter_map(move |(index, c)| {
+    match (quote, c) {
+      (Some(q), c) if c == q => quote = None,
+      (Some(_), _) => {}
+      (None, '"' | '\'' | '`') => quote = Some(c),
+      (None, '(') => depth += 1,
+      (None, ')') => depth = depth.saturating_sub(1),
+      (None, c) if c == target && depth == 0 => return Some(index),
+      _ => {}
+    }
+    None
+  })
+}
+
+fn unquote(value: &str) -> &str {
+  value
+    .strip_prefix('"')
+    .and_then(|v| v.strip_suffix('"'))
+    .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+    .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod just_tests {
+  use super::{parse_recipes, Just, JustParams};
+  use crate::{runnables::RunRunnable, types::Runnable};
+
+  #[test]
+  fn parse_justfile_recipes() {
+    let contents = r#"
+set shell := ["bash", "-c"]
+version := "1.0.0"
+
+alias b := build
+
+# Build the project
+build target="debug":
+  cargo build --profile {{target}}
+
+[private]
+helper:
+  echo helper
+
+_hidden:
+  echo hidden
+
+[doc('Serve on a port')]
+@serve port=":8080" +args: build
+  ./serve {{port}} {{args}}
+"#;
+    let recipes = parse_recipes(contents);
+    let names = recipes.keys().map(String::as_str).collect::<Vec<_>>();
+    assert_eq!(names, ["build", "helper", "_hidden", "serve"]);
+
+    let build = &recipes["build"];
+    assert_eq!(build.doc.as_deref(), Some("Build the project"));
+    assert_eq!(build.parameters, [r#"target="debug""#]);
+    assert_eq!(build.aliases, ["b"]);
+    assert!(!build.private);
+
+    assert!(recipes["helper"].private);
+    assert!(recipes["_hidden"].private);
+
+    let serve = &recipes["serve"];
+    assert_eq!(serve.doc.as_deref(), Some("Serve on a port"));
+    assert_eq!(serve.parameters, [r#"port=":8080""#, "+args"]);
+  }
+
+  #[test]
+  fn required_parameters_are_passed_in_order() {
+    let recipes = parse_recipes("deploy env $region='eu' +hosts *flags:\n  ./deploy.sh\n");
+    let mut params = JustParams {
+      parameters: recipes["deploy"].parameters.clone(),
+      ..Default::default()
+    };
+    // The region comes before the required hosts, so it is passed too.
+    let positional = params
+      .positional()
+      .into_iter()
+      .map(|parameter| (parameter.name.clone(), parameter.default_value()))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      positional,
+      [
+        (String::from("env"), None),
+        (String::from("region"), Some(String::from("eu"))),
+        (String::from("hosts"), None),
+      ]
+    );
+
+    let given = [(String::from("env"), String::from("prod"))];
+    let error = params.resolve("deploy", &given).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "missing value for required parameter 'hosts' of 'deploy', pass it with --param hosts=<value>"
+    );
+
+    params.values.insert(
+      String::from("hosts"),
+      String::from("a.example.com b.example.com"),
+    );
+    params.resolve("deploy", &given).unwrap();
+    assert_eq!(
+      params.args(),
+      ["prod", "eu", "a.example.com", "b.example.com"]
+    );
+    let runnable = Runnable {
+      name: String::from("deploy"),
+      ..Default::default()
+    };
+    assert_eq!(
+      Just::invocation(&runnable, &params).to_string(),
+      "just deploy prod eu a.example.com b.example.com"
+    );
+  }
+}
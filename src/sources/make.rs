@@ -0,0 +1,197 @@
+use std::{fs, path::Path};
+
+use anyhow::anyhow;
+use indexmap::IndexMap;
+
+use crate::{
+  invocation::Invocation,
+  runnables::{AddRunnables, RunRunnable},
+  types::{Runnable, RunnableParams},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct MakeParams {
+  /// Whether the target is listed as a prerequisite of `.PHONY`.
+  pub phony: bool,
+}
+
+/// Makefile names in the order GNU make looks for them,
+/// so `make <target>` picks up the same file.
+const MAKEFILES: &[&str] = &["GNUmakefile", "makefile", "Makefile"];
+
+pub struct Make;
+
+impl AddRunnables for Make {
+  fn add_runnable(path: &Path, runnables: &mut Vec<Runnable>) -> anyhow::Result<()> {
+    let contents = MAKEFILES
+      .iter()
+      .find_map(|makefile| fs::read_to_string(path.join(makefile)).ok())
+      .ok_or_else(|| anyhow!("directory does not include Makefile"))?;
+    runnables.extend(
+      parse_targets(&contents)
+        .into_iter()
+        .map(|(name, target)| Runnable {
+          name,
+          aliases: Default::default(),
+          display_name: None,
+          description: target.description,
+          after: None,
+          path: path.to_owned(),
+          index: 0,
+          params: RunnableParams::Make(MakeParams {
+            phony: target.phony,
+          }),
+        }),
+    );
+    Ok(())
+  }
+}
+
+impl RunRunnable for Make {
+  type Params = MakeParams;
+
+  fn invocation(runnable: &Runnable, _: &Self::Params) -> Invocation {
+    Invocation::new("make")
+      .arg(&runnable.name)
+      .dir(&runnable.path)
+  }
+}
+
+#[derive(Default)]
+struct MakeTarget {
+  description: Option<String>,
+  phony: bool,
+}
+
+/// Parses the explicit targets out of a Makefile, in declaration order.
+///
+/// Skips special (`.`-prefixed), pattern (`%`) and variable (`$`) targets,
+/// and uses a trailing `## comment` on the rule line as the description.
+fn parse_targets(contents: &str) -> IndexMap<String, MakeTarget> {
+  let mut targets = IndexMap::<String, MakeTarget>::new();
+  let mut phony = Vec::<String>::new();
+  let mut in_define = false;
+
+  for line in join_continuations(contents) {
+    // Recipe lines are indented with a tab.
+    if line.starts_with('\t') {
+      continue;
+    }
+    let trimmed = line.trim();
+    if in_define {
+      in_define = trimmed != "endef";
+      continue;
+    }
+    if trimmed.starts_with("define ") || trimmed == "define" {
+      in_define = true;
+      continue;
+    }
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+      continue;
+    }
+
+    let (rule, description) = match line.split_once("##") {
+      Some((rule, description)) => (rule, Some(description.trim().to_string())),
+      None => (line.as_str(), None),
+    };
+    let rule = rule.split('#').next().unwrap_or_default();
+
+    let Some((names, prerequisites)) = rule.split_once(':') else {
+      continue;
+    };
+    // Variable assignments: `A := b`, `A ::= b`, `A = b:c`
+    if names.contains('=') || prerequisites.starts_with('=') || prerequisites.starts_with(":=") {
+      continue;
+    }
+    let prerequisites = prerequisites.trim_start_matches(':');
+    // Target specific variables: `target: VAR = value`
+    if prerequisites.contains('=') {
+      continue;
+    }
+
+    let names = names.split_whitespace().collect::<Vec<_>>();
+    if names == [".PHONY"] {
+      phony.extend(prerequisites.split_whitespace().map(str::to_string));
+      continue;
+    }
+
+    for name in names {
+      if name.starts_with('.') || name.contains('%') || name.contains('$') {
+        continue;
+      }
+      let target = targets.entry(name.to_string()).or_default();
+      if target.description.is_none() {
+        target.description = description.clone().filter(|d| !d.is_empty());
+      }
+    }
+  }
+
+  for name in phony {
+    if let Some(target) = targets.get_mut(&name) {
+      target.phony = true;
+    }
+  }
+
+  targets
+}
+
+/// Joins lines ending in a `\` with the line that follows.
+fn join_continuations(contents: &str) -> Vec<String> {
+  let mut lines = Vec::new();
+  let mut current = String::new();
+  for line in contents.lines() {
+    match line.strip_suffix('\\') {
+      Some(line) => {
+        current.push_str(line);
+        current.push(' ');
+      }
+      None => {
+        current.push_str(line);
+        lines.push(std::mem::take(&mut current));
+      }
+    }
+  }
+  if !current.is_empty() {
+    lines.push(current);
+  }
+  lines
+}
+
+#[cfg(test)]
+mod make_tests {
+  use super::parse_targets;
+
+  #[test]
+  fn parse_makefile_targets() {
+    let contents = "\
+CC := gcc
+SRC = main.c
+
+.PHONY: build \\
+  clean
+
+build: main.o ## Build the binary
+\t$(CC) -o main main.o
+
+%.o: %.c
+\t$(CC) -c $<
+
+clean: ## Remove build artifacts
+\trm -f main *.o
+
+.DEFAULT_GOAL := build
+install: build
+";
+    let targets = parse_targets(contents);
+    let names = targets.keys().map(String::as_str).collect::<Vec<_>>();
+    assert_eq!(names, ["build", "clean", "install"]);
+    assert_eq!(
+      targets["build"].description.as_deref(),
+      Some("Build the binary")
+    );
+    assert!(targets["build"].phony);
+    assert!(targets["clean"].phony);
+    assert!(!targets["install"].phony);
+    assert!(targets["install"].description.is_none());
+  }
+}
@@ -1,23 +1,43 @@
 use std::{
   fs,
   path::{Path, PathBuf},
-  rc::Rc,
+  process::ExitStatus,
   str::FromStr,
+  sync::mpsc,
+  thread,
 };
 
+use colored::{Color, Colorize};
+use derive_variants::ExtractVariant;
+
 use crate::{
-  runnables::{ignore_dir, AddRunnables, RunRunnable},
+  graph::Step,
+  runnables::{ignore_dir, AddRunnables, Output, RunOutcome, RunRunnable},
   types::{Runnable, RunnableParams, RunnableParamsVariant},
   CliArgs,
 };
 
-use self::{runfile::RunFile, rust_bin::RustBin, rust_lib::RustLib, shell::Shell};
+use self::{
+  cargo_alias::CargoAlias, cargo_metadata::CargoMetadata, go::Go, javascript::Javascript,
+  just::Just, make::Make, python::Python, runfile::RunFile, rust_bin::RustBin, rust_lib::RustLib,
+  rust_workspace::RustWorkspace, shell::Shell, taskfile::Taskfile,
+};
 
-// pub mod javascript;
+pub mod cargo_alias;
+pub mod cargo_metadata;
+pub mod cargo_options;
+pub mod cargo_toml;
+pub mod go;
+pub mod javascript;
+pub mod just;
+pub mod make;
+pub mod python;
 pub mod runfile;
 pub mod rust_bin;
 pub mod rust_lib;
+pub mod rust_workspace;
 pub mod shell;
+pub mod taskfile;
 
 pub fn get_runnables(args: &CliArgs) -> anyhow::Result<Vec<Runnable>> {
   let path = PathBuf::from_str(&args.path)?;
@@ -33,24 +53,175 @@ pub fn get_runnables(args: &CliArgs) -> anyhow::Result<Vec<Runnable>> {
   if !args.ignore.contains(&RunnableParamsVariant::Shell) {
     Shell::add_runnables(&path, &runincludes, &runignores, &mut runnables);
   }
-  if !args.ignore.contains(&RunnableParamsVariant::RustBin) {
-    RustBin::add_runnables(&path, &runincludes, &runignores, &mut runnables);
+  if args.cargo_metadata {
+    // Picks up both bin and lib runnables, ignored types are filtered below.
+    CargoMetadata::add_runnables(&path, &runincludes, &runignores, &mut runnables);
+  } else {
+    if !args.ignore.contains(&RunnableParamsVariant::RustBin) {
+      RustBin::add_runnables(&path, &runincludes, &runignores, &mut runnables);
+    }
+    if !args.ignore.contains(&RunnableParamsVariant::RustLib) {
+      RustLib::add_runnables(&path, &runincludes, &runignores, &mut runnables);
+    }
+  }
+  if !args.ignore.contains(&RunnableParamsVariant::RustWorkspace) {
+    RustWorkspace::add_runnables(&path, &runincludes, &runignores, &mut runnables);
+  }
+  if !args.ignore.contains(&RunnableParamsVariant::CargoAlias) {
+    CargoAlias::add_runnables(&path, &runincludes, &runignores, &mut runnables);
+  }
+  if !args.ignore.contains(&RunnableParamsVariant::Javascript) {
+    Javascript::add_runnables(&path, &runincludes, &runignores, &mut runnables);
+  }
+  if !args.ignore.contains(&RunnableParamsVariant::Make) {
+    Make::add_runnables(&path, &runincludes, &runignores, &mut runnables);
   }
-  if !args.ignore.contains(&RunnableParamsVariant::RustLib) {
-    RustLib::add_runnables(&path, &runincludes, &runignores, &mut runnables);
+  if !args.ignore.contains(&RunnableParamsVariant::Just) {
+    Just::add_runnables(&path, &runincludes, &runignores, &mut runnables);
   }
+  if !args.ignore.contains(&RunnableParamsVariant::Taskfile) {
+    Taskfile::add_runnables(&path, &runincludes, &runignores, &mut runnables);
+  }
+  if !args.ignore.contains(&RunnableParamsVariant::Python) {
+    Python::add_runnables(&path, &runincludes, &runignores, &mut runnables);
+  }
+  if !args.ignore.contains(&RunnableParamsVariant::Go) {
+    Go::add_runnables(&path, &runincludes, &runignores, &mut runnables);
+  }
+
+  runnables.retain(|runnable| !args.ignore.contains(&runnable.params.extract_variant()));
 
   Ok(runnables)
 }
 
-pub fn run_runnable(runnable: &Runnable, runnables: &[Rc<Runnable>]) {
+/// Runs the prerequisites, then the runnable.
+/// With more than one job, prerequisites which don't depend on each other run at the same time.
+/// A failing prerequisite stops the chain, unless it is set to continue on error.
+/// Ctrl-C or a signal stops it regardless.
+/// The extra `args` are only passed to the runnable itself.
+pub fn run_runnable(
+  runnable: &Runnable,
+  prerequisites: &[Step],
+  jobs: usize,
+  args: &[String],
+  output: Output,
+) -> RunOutcome {
+  let outcome = if jobs > 1 {
+    run_parallel(prerequisites, jobs, output)
+  } else {
+    run_sequential(prerequisites, output)
+  };
+  let mut outcome = match outcome {
+    Some(outcome) => outcome,
+    None => run_single(runnable, args, output).into(),
+  };
+  outcome.interrupted = output.groups().interrupted();
+  outcome
+}
+
+/// Returns the outcome if a step broke the chain.
+fn run_sequential(steps: &[Step], output: Output) -> Option<RunOutcome> {
+  for step in steps {
+    let status = run_single(&step.runnable, &[], output);
+    if output.groups().interrupted() || !status.success() && !step.runnable.continue_on_error() {
+      return Some(RunOutcome {
+        broken_by: Some(step.runnable.name.clone()),
+        ..status.into()
+      });
+    }
+  }
+  None
+}
+
+/// Runs up to `jobs` steps at once, starting each as soon as the steps it runs after are done.
+/// After a step breaks the chain or the run is interrupted,
+/// no new steps are started and the running ones are waited on.
+fn run_parallel(steps: &[Step], jobs: usize, output: Output) -> Option<RunOutcome> {
+  const COLORS: &[Color] = &[
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Green,
+    Color::Blue,
+    Color::Red,
+  ];
+  let width = steps
+    .iter()
+    .map(|step| step.runnable.name.len())
+    .max()
+    .unwrap_or_default();
+  let prefixes = steps
+    .iter()
+    .enumerate()
+    .map(|(i, step)| {
+      format!("{:width$} |", step.runnable.name)
+        .color(COLORS[i % COLORS.len()])
+        .to_string()
+    })
+    .collect::<Vec<_>>();
+
+  let mut started = vec![false; steps.len()];
+  let mut finished = vec![false; steps.len()];
+  let mut running = 0;
+  let mut broken = None;
+  let (sender, receiver) = mpsc::channel();
+  thread::scope(|scope| loop {
+    if broken.is_none() && !output.groups().interrupted() {
+      for (i, step) in steps.iter().enumerate() {
+        if running == jobs {
+          break;
+        }
+        if started[i] || !step.after.iter().all(|&after| finished[after]) {
+          continue;
+        }
+        started[i] = true;
+        running += 1;
+        let runnable: &Runnable = &step.runnable;
+        let prefix = &prefixes[i];
+        let sender = sender.clone();
+        scope.spawn(move || {
+          let status = run_single(runnable, &[], output.prefixed(prefix));
+          sender.send((i, status)).ok();
+        });
+      }
+    }
+    if running == 0 {
+      break;
+    }
+    let Ok((i, status)) = receiver.recv() else {
+      break;
+    };
+    running -= 1;
+    finished[i] = true;
+    let runnable = &steps[i].runnable;
+    let failed = !status.success() && !runnable.continue_on_error();
+    if broken.is_none() && (output.groups().interrupted() || failed) {
+      broken = Some(RunOutcome {
+        broken_by: Some(runnable.name.clone()),
+        ..status.into()
+      });
+    }
+  });
+  broken
+}
+
+fn run_single(runnable: &Runnable, args: &[String], output: Output) -> ExitStatus {
   match &runnable.params {
-    RunnableParams::RunFile(params) => RunFile::run(runnable, params, runnables),
-    RunnableParams::Shell(params) => Shell::run(runnable, params, runnables),
-    RunnableParams::RustBin(params) => RustBin::run(runnable, params, runnables),
-    RunnableParams::RustLib(params) => RustLib::run(runnable, params, runnables),
+    RunnableParams::RunFile(params) => RunFile::run(runnable, params, args, output),
+    RunnableParams::Shell(params) => Shell::run(runnable, params, args, output),
+    RunnableParams::RustBin(params) => RustBin::run(runnable, params, args, output),
+    RunnableParams::RustLib(params) => RustLib::run(runnable, params, args, output),
+    RunnableParams::RustWorkspace(params) => RustWorkspace::run(runnable, params, args, output),
+    RunnableParams::CargoAlias(params) => CargoAlias::run(runnable, params, args, output),
+    RunnableParams::Javascript(params) => Javascript::run(runnable, params, args, output),
+    RunnableParams::Make(params) => Make::run(runnable, params, args, output),
+    RunnableParams::Just(params) => Just::run(runnable, params, args, output),
+    RunnableParams::Taskfile(params) => Taskfile::run(runnable, params, args, output),
+    RunnableParams::Python(params) => Python::run(runnable, params, args, output),
+    RunnableParams::Go(params) => Go::run(runnable, params, args, output),
     RunnableParams::None => {
-      println!("got NONE runnable")
+      println!("got NONE runnable");
+      ExitStatus::default()
     }
   }
 }
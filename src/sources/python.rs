@@ -0,0 +1,433 @@
+use std::{fmt::Display, fs, path::Path};
+
+use anyhow::Context;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{
+  invocation::Invocation,
+  runnables::{AddRunnables, RunRunnable},
+  types::{Runnable, RunnableParams},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct PythonParams {
+  pub runner: PythonRunner,
+  pub target: PythonTarget,
+  pub command: PythonCommand,
+}
+
+impl PythonParams {
+  /// The command to run, without changing directory.
+  pub fn invocation(&self, name: &str) -> Invocation {
+    let runner = self.runner;
+    match (&self.target, self.command) {
+      (PythonTarget::Script { entry_point }, PythonCommand::Run) => match runner {
+        // Without an environment manager the console script may not be installed,
+        // so call the entry point directly.
+        PythonRunner::Python => match entry_point_call(entry_point) {
+          Some(code) => Invocation::new("python").args(["-c", &code]),
+          None => Invocation::words(&runner.to_string()).arg(entry_point.trim()),
+        },
+        _ => Invocation::words(&runner.to_string()).arg(name),
+      },
+      (PythonTarget::PdmScript, PythonCommand::Run) => Invocation::words("pdm run").arg(name),
+      (PythonTarget::HatchScript { env }, PythonCommand::Run) => {
+        if env == "default" {
+          Invocation::words("hatch run").arg(name)
+        } else {
+          Invocation::words("hatch run").arg(format!("{env}:{name}"))
+        }
+      }
+      // The project has nothing to run on its own, so it runs its default action, the tests.
+      (PythonTarget::Project, PythonCommand::Run) => {
+        Invocation::words(&format!("{runner} {}", PythonCommand::Test))
+      }
+      (_, command) => Invocation::words(&format!("{runner} {command}")),
+    }
+  }
+
+  /// The command line shown before running.
+  pub fn command_line(&self, name: &str) -> String {
+    self.invocation(name).to_string()
+  }
+}
+
+/// Python code calling an entry point like `pkg.cli:main` or `pkg.cli:App.run`,
+/// exiting with what it returns like the console script would.
+/// None for an entry point which is only a module.
+fn entry_point_call(entry_point: &str) -> Option<String> {
+  // Drop the extras, eg. `pkg.cli:main [cli]`.
+  let entry_point = entry_point.split('[').next()?.trim();
+  let (module, attribute) = entry_point.split_once(':')?;
+  let (module, attribute) = (module.trim(), attribute.trim());
+  let object = attribute.split('.').next()?;
+  Some(format!(
+    "import sys; from {module} import {object}; sys.exit({attribute}())"
+  ))
+}
+
+/// How to invoke commands inside the project environment.
+/// Picked from the lockfile present in the project directory.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum PythonRunner {
+  Uv,
+  Poetry,
+  Pdm,
+  #[default]
+  Python,
+}
+
+impl Display for PythonRunner {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let d = match self {
+      PythonRunner::Uv => "uv run",
+      PythonRunner::Poetry => "poetry run",
+      PythonRunner::Pdm => "pdm run",
+      PythonRunner::Python => "python -m",
+    };
+    f.write_str(d)
+  }
+}
+
+impl PythonRunner {
+  fn from_lockfile(path: &Path) -> PythonRunner {
+    const LOCKFILES: &[(&str, PythonRunner)] = &[
+      ("uv.lock", PythonRunner::Uv),
+      ("poetry.lock", PythonRunner::Poetry),
+      ("pdm.lock", PythonRunner::Pdm),
+    ];
+    LOCKFILES
+      .iter()
+      .find(|(lockfile, _)| path.join(lockfile).is_file())
+      .map(|(_, runner)| *runner)
+      .unwrap_or_default()
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum PythonTarget {
+  /// The project itself, for the test / lint actions.
+  #[default]
+  Project,
+  /// `[project.scripts]` or `[tool.poetry.scripts]` entry point, eg. `my_pkg.cli:main`.
+  Script { entry_point: String },
+  /// `[tool.pdm.scripts]` entry.
+  PdmScript,
+  /// `[tool.hatch.envs.<env>.scripts]` entry.
+  HatchScript { env: String },
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub enum PythonCommand {
+  #[default]
+  Run,
+  Test,
+  Lint,
+  Format,
+  TypeCheck,
+}
+
+impl Display for PythonCommand {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let d = match self {
+      PythonCommand::Run => "python",
+      PythonCommand::Test => "pytest",
+      PythonCommand::Lint => "ruff check",
+      PythonCommand::Format => "ruff format",
+      PythonCommand::TypeCheck => "mypy .",
+    };
+    f.write_str(d)
+  }
+}
+
+#[derive(Deserialize)]
+struct PyprojectToml {
+  project: Option<PyprojectProject>,
+  #[serde(default)]
+  tool: PyprojectTool,
+}
+
+#[derive(Deserialize)]
+struct PyprojectProject {
+  name: Option<String>,
+  description: Option<String>,
+  /// An entry point, or for poetry also a table like `{ callable = "pkg.cli:main", extras = [...] }`.
+  #[serde(default)]
+  scripts: IndexMap<String, toml::Value>,
+}
+
+#[derive(Deserialize, Default)]
+struct PyprojectTool {
+  poetry: Option<PyprojectProject>,
+  pdm: Option<PyprojectPdm>,
+  hatch: Option<PyprojectHatch>,
+}
+
+#[derive(Deserialize)]
+struct PyprojectPdm {
+  #[serde(default)]
+  scripts: IndexMap<String, toml::Value>,
+}
+
+#[derive(Deserialize)]
+struct PyprojectHatch {
+  #[serde(default)]
+  envs: IndexMap<String, PyprojectHatchEnv>,
+}
+
+#[derive(Deserialize)]
+struct PyprojectHatchEnv {
+  #[serde(default)]
+  scripts: IndexMap<String, toml::Value>,
+}
+
+pub struct Python;
+
+impl AddRunnables for Python {
+  fn add_runnable(path: &Path, runnables: &mut Vec<Runnable>) -> anyhow::Result<()> {
+    let contents = fs::read_to_string(path.join("pyproject.toml"))
+      .context("directory does not include pyproject.toml")?;
+    let runner = PythonRunner::from_lockfile(path);
+    runnables.extend(parse_runnables(&contents, path, runner)?);
+    Ok(())
+  }
+}
+
+impl RunRunnable for Python {
+  type Params = PythonParams;
+
+  fn invocation(runnable: &Runnable, params: &Self::Params) -> Invocation {
+    params.invocation(&runnable.name).dir(&runnable.path)
+  }
+}
+
+/// The project runnable, followed by its scripts.
+fn parse_runnables(
+  contents: &str,
+  path: &Path,
+  runner: PythonRunner,
+) -> anyhow::Result<Vec<Runnable>> {
+  let PyprojectToml { project, tool } =
+    toml::from_str(contents).context("failed to parse pyproject.toml")?;
+
+  let name = project
+    .as_ref()
+    .and_then(|project| project.name.clone())
+    .or_else(|| tool.poetry.as_ref().and_then(|poetry| poetry.name.clone()))
+    .or_else(|| {
+      path
+        .canonicalize()
+        .ok()?
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+    })
+    .unwrap_or_else(|| String::from("python"));
+  let description = project
+    .as_ref()
+    .and_then(|project| project.description.clone())
+    .or_else(|| {
+      tool
+        .poetry
+        .as_ref()
+        .and_then(|poetry| poetry.description.clone())
+    });
+
+  let runnable = |script: String, description: Option<String>, target: PythonTarget| Runnable {
+    display_name: Some(format!("{name} / {script}")),
+    name: script,
+    aliases: Default::default(),
+    description,
+    after: None,
+    path: path.to_owned(),
+    index: 0,
+    params: RunnableParams::Python(PythonParams {
+      runner,
+      target,
+      command: PythonCommand::Run,
+    }),
+  };
+
+  let entry_points = project
+    .into_iter()
+    .chain(tool.poetry)
+    .flat_map(|project| project.scripts);
+  let mut scripts = Vec::new();
+  for (script, value) in entry_points {
+    let Some(entry_point) = entry_point(&value) else {
+      continue;
+    };
+    scripts.push(runnable(
+      script,
+      Some(entry_point.clone()),
+      PythonTarget::Script { entry_point },
+    ));
+  }
+  for (script, value) in tool.pdm.into_iter().flat_map(|pdm| pdm.scripts) {
+    scripts.push(runnable(
+      script,
+      pdm_script_description(&value),
+      PythonTarget::PdmScript,
+    ));
+  }
+  for (env, hatch_env) in tool.hatch.into_iter().flat_map(|hatch| hatch.envs) {
+    for (script, value) in hatch_env.scripts {
+      scripts.push(runnable(
+        script,
+        commands_description(&value),
+        PythonTarget::HatchScript { env: env.clone() },
+      ));
+    }
+  }
+
+  let project = Runnable {
+    name,
+    aliases: Default::default(),
+    display_name: None,
+    description,
+    after: None,
+    path: path.to_owned(),
+    index: 0,
+    params: RunnableParams::Python(PythonParams {
+      runner,
+      target: PythonTarget::Project,
+      command: PythonCommand::Test,
+    }),
+  };
+  Ok([project].into_iter().chain(scripts).collect())
+}
+
+/// The entry point of a script, skipping poetry's `type = "file"` scripts.
+fn entry_point(value: &toml::Value) -> Option<String> {
+  let table = match value {
+    toml::Value::String(entry_point) => return Some(entry_point.clone()),
+    toml::Value::Table(table) => table,
+    _ => return None,
+  };
+  if table
+    .get("type")
+    .and_then(toml::Value::as_str)
+    .is_some_and(|kind| kind != "console")
+  {
+    return None;
+  }
+  ["callable", "reference"]
+    .iter()
+    .find_map(|key| table.get(*key)?.as_str())
+    .map(str::to_string)
+}
+
+/// pdm scripts are either a command string,
+/// or a table with one of `cmd`, `shell`, `call` or `composite`, and an optional `help`.
+fn pdm_script_description(value: &toml::Value) -> Option<String> {
+  let Some(table) = value.as_table() else {
+    return commands_description(value);
+  };
+  if let Some(help) = table.get("help").and_then(toml::Value::as_str) {
+    return Some(help.to_string());
+  }
+  if let Some(toml::Value::Array(args)) = table.get("cmd") {
+    let args = args.iter().filter_map(toml::Value::as_str);
+    return Some(args.collect::<Vec<_>>().join(" "));
+  }
+  ["cmd", "shell", "call", "composite"]
+    .iter()
+    .find_map(|key| table.get(*key))
+    .and_then(commands_description)
+}
+
+/// Describes a command string, or a list of commands / command args.
+fn commands_description(value: &toml::Value) -> Option<String> {
+  match value {
+    toml::Value::String(command) => Some(command.clone()),
+    toml::Value::Array(commands) => Some(
+      commands
+        .iter()
+        .filter_map(toml::Value::as_str)
+        .collect::<Vec<_>>()
+        .join(" && "),
+    ),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod python_tests {
+  use std::path::Path;
+
+  use super::{parse_runnables, PythonCommand, PythonParams, PythonRunner, PythonTarget};
+  use crate::types::RunnableParams;
+
+  #[test]
+  fn parse_pyproject_scripts() {
+    let contents = r#"
+[tool.poetry]
+name = "my-app"
+description = "An app"
+
+[tool.poetry.scripts]
+serve = "my_app.cli:main"
+admin = { callable = "my_app.admin:App.run", extras = ["admin"] }
+setup = { reference = "scripts/setup.sh", type = "file" }
+
+[tool.pdm.scripts]
+lint = { cmd = ["ruff", "check"], help = "Lint the code" }
+"#;
+    let runnables = parse_runnables(contents, Path::new("."), PythonRunner::Poetry).unwrap();
+    let names = runnables
+      .iter()
+      .map(|runnable| runnable.name.as_str())
+      .collect::<Vec<_>>();
+    // Without toml's `preserve_order`, tables come back sorted.
+    assert_eq!(names, ["my-app", "admin", "serve", "lint"]);
+    assert_eq!(runnables[0].description.as_deref(), Some("An app"));
+    assert_eq!(
+      runnables[1].description.as_deref(),
+      Some("my_app.admin:App.run")
+    );
+    assert_eq!(runnables[3].description.as_deref(), Some("Lint the code"));
+    let RunnableParams::Python(params) = &runnables[2].params else {
+      panic!("expected a python runnable");
+    };
+    assert_eq!(params.invocation("serve").to_string(), "poetry run serve");
+  }
+
+  #[test]
+  fn plain_python_calls_the_entry_point() {
+    let params = |entry_point: &str| PythonParams {
+      runner: PythonRunner::Python,
+      target: PythonTarget::Script {
+        entry_point: entry_point.to_string(),
+      },
+      command: PythonCommand::Run,
+    };
+    let invocation = params("my_app.cli:main").invocation("serve");
+    assert_eq!(invocation.program, "python");
+    assert_eq!(
+      invocation.args,
+      [
+        "-c",
+        "import sys; from my_app.cli import main; sys.exit(main())"
+      ]
+    );
+    let invocation = params("my_app.admin:App.run [admin]").invocation("admin");
+    assert_eq!(
+      invocation.args[1],
+      "import sys; from my_app.admin import App; sys.exit(App.run())"
+    );
+    let invocation = params("my_app.tool").invocation("tool");
+    assert_eq!(invocation.args, ["-m", "my_app.tool"]);
+  }
+
+  #[test]
+  fn project_runs_its_actions() {
+    let params = |command| PythonParams {
+      runner: PythonRunner::Uv,
+      target: PythonTarget::Project,
+      command,
+    };
+    let command_line = |command| params(command).invocation("my-app").to_string();
+    assert_eq!(command_line(PythonCommand::Lint), "uv run ruff check");
+    assert_eq!(command_line(PythonCommand::Run), "uv run pytest");
+  }
+}
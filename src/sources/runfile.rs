@@ -1,9 +1,18 @@
-use std::{fs, path::Path};
+use std::{
+  fmt::Display,
+  fs,
+  path::{Path, PathBuf},
+};
 
+use anyhow::anyhow;
 use indexmap::IndexMap;
 use serde::Deserialize;
 
 use crate::{
+  config::config,
+  dotenv,
+  helpers::shell_quote,
+  invocation::{is_posix_shell, shell_name, Invocation},
   runnables::{AddRunnables, RunRunnable},
   types::{Runnable, RunnableParams},
 };
@@ -11,10 +20,219 @@ use crate::{
 #[derive(Debug, Clone, Default)]
 pub struct RunFileParams {
   pub command: String,
+  pub continue_on_error: bool,
+  pub parallel: bool,
+  /// The declared params of the command.
+  pub params: Vec<RunFileParam>,
+  /// The values filled in for the params, by name.
+  pub values: IndexMap<String, String>,
+  /// The variables set for the command, from the `env_file`s and `env`.
+  pub env: IndexMap<String, String>,
+  /// The `env_file`s which couldn't be read.
+  pub missing_env_files: Vec<PathBuf>,
+  /// The shell to run the command with, instead of the configured default.
+  pub shell: Option<String>,
+}
+
+impl RunFileParams {
+  /// The command with the `{{name}}` placeholders replaced by the values,
+  /// quoted for the `shell` which runs it.
+  pub fn command_line(&self, shell: &str) -> String {
+    let mut command = self.command.clone();
+    for (name, value) in &self.values {
+      let value = quote_for(shell, value);
+      command = command
+        .replace(&format!("{{{{{name}}}}}"), &value)
+        .replace(&format!("{{{{ {name} }}}}"), &value);
+    }
+    command
+  }
+
+  /// The shell to run the command with: the item's, then the `configured` default, then sh.
+  pub fn shell<'a>(&'a self, configured: Option<&'a str>) -> &'a str {
+    self.shell.as_deref().or(configured).unwrap_or("sh")
+  }
+
+  /// Fills in the value of every param, from the values already set (eg. in the interface),
+  /// then the `given` values (eg. from `--param`), then the default.
+  /// Fails if a required param has no value or a value is invalid,
+  /// or if an `env_file` couldn't be read.
+  pub fn resolve(&mut self, name: &str, given: &[(String, String)]) -> anyhow::Result<()> {
+    if let Some(env_file) = self.missing_env_files.first() {
+      return Err(anyhow!(
+        "env_file of '{name}' could not be read: {}",
+        env_file.display()
+      ));
+    }
+    for param in &self.params {
+      let value = match self.values.get(&param.name) {
+        Some(value) => value.clone(),
+        None => {
+          let given = given
+            .iter()
+            .rev()
+            .find(|(name, _)| *name == param.name)
+            .map(|(_, value)| value.clone());
+          match given.or_else(|| param.default_value()) {
+            Some(value) => value,
+            None => {
+              return Err(anyhow!(
+              "missing value for required param '{}' of '{name}', pass it with --param {}=<value>",
+              param.name,
+              param.name
+            ))
+            }
+          }
+        }
+      };
+      param
+        .validate(&value)
+        .map_err(|e| anyhow!("invalid value for param '{}' of '{name}': {e}", param.name))?;
+      self.values.insert(param.name.clone(), value);
+    }
+    Ok(())
+  }
+}
+
+/// Quotes the value as a string for the shell: sh quoting for POSIX shells,
+/// fish quoting for fish, and a double quoted literal with backslash escapes for
+/// other interpreters, eg. `python3` or `node`.
+fn quote_for(shell: &str, value: &str) -> String {
+  if is_posix_shell(shell) {
+    return shell_quote(value);
+  }
+  match shell_name(shell) {
+    "fish" => format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'")),
+    _ => serde_json::to_string(value).unwrap_or_default(),
+  }
+}
+
+/// A value the command takes, substituted into `{{name}}` placeholders.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RunFileParam {
+  pub name: String,
+  pub description: Option<String>,
+  /// Optional. The param is required when there is no default.
+  pub default: Option<toml::Value>,
+  /// Optional. The only values allowed.
+  #[serde(default)]
+  pub choices: Vec<toml::Value>,
+  /// Optional. Default: string
+  #[serde(default, rename = "type")]
+  pub kind: RunFileParamType,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunFileParamType {
+  #[default]
+  String,
+  Int,
+  Bool,
+  Path,
+}
+
+impl Display for RunFileParamType {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let d = match self {
+      RunFileParamType::String => "string",
+      RunFileParamType::Int => "int",
+      RunFileParamType::Bool => "bool",
+      RunFileParamType::Path => "path",
+    };
+    f.write_str(d)
+  }
+}
+
+impl RunFileParam {
+  pub fn default_value(&self) -> Option<String> {
+    self.default.as_ref().map(value_string)
+  }
+
+  pub fn choices(&self) -> Vec<String> {
+    self.choices.iter().map(value_string).collect()
+  }
+
+  pub fn validate(&self, value: &str) -> anyhow::Result<()> {
+    let choices = self.choices();
+    if !choices.is_empty() && !choices.iter().any(|choice| choice == value) {
+      return Err(anyhow!("'{value}' is not one of {}", choices.join(", ")));
+    }
+    match self.kind {
+      RunFileParamType::String => Ok(()),
+      RunFileParamType::Int => value
+        .parse::<i64>()
+        .map(|_| ())
+        .map_err(|_| anyhow!("'{value}' is not an int")),
+      RunFileParamType::Bool => match value {
+        "true" | "false" => Ok(()),
+        _ => Err(anyhow!("'{value}' is not true or false")),
+      },
+      RunFileParamType::Path if value.is_empty() => Err(anyhow!("the path is empty")),
+      RunFileParamType::Path => Ok(()),
+    }
+  }
+}
+
+/// Strings are used as is, other values in their toml form, eg. `8080` or `true`.
+fn value_string(value: &toml::Value) -> String {
+  match value {
+    toml::Value::String(value) => value.clone(),
+    value => value.to_string(),
+  }
 }
 
 pub type RunFileContent = IndexMap<String, RunFileItem>;
 
+/// The environment shared by all the items of a runfile, set with top level `env` / `env_file` keys.
+#[derive(Deserialize, Default)]
+pub struct RunFileEnv {
+  /// Optional. Variables to set, eg. `env = { RUST_LOG = "debug" }`.
+  #[serde(default)]
+  pub env: IndexMap<String, String>,
+  /// Optional. Dotenv files to read variables from, relative to the directory
+  /// which contains the `runfile.toml`. Later files override earlier ones.
+  #[serde(
+    default,
+    deserialize_with = "crate::deserializers::string_list_deserializer"
+  )]
+  pub env_file: Vec<String>,
+}
+
+impl RunFileEnv {
+  /// Applies the `env_file`s, then `env`, over the variables already set.
+  fn apply(&self, dir: &Path, env: &mut IndexMap<String, String>, missing: &mut Vec<PathBuf>) {
+    for env_file in &self.env_file {
+      let env_file = dir.join(env_file);
+      match dotenv::read(&env_file) {
+        Ok(vars) => env.extend(vars),
+        Err(_) => missing.push(env_file),
+      }
+    }
+    env.extend(self.env.clone());
+  }
+}
+
+/// Splits the top level `env` / `env_file` defaults from the items.
+/// A top level `env` table with a `cmd` is an item named `env` instead.
+fn parse_runfile(contents: &str) -> anyhow::Result<(RunFileEnv, RunFileContent)> {
+  let mut table: toml::Table = toml::from_str(contents)?;
+  let mut defaults = toml::Table::new();
+  if let Some(env_file) = table.remove("env_file") {
+    defaults.insert(String::from("env_file"), env_file);
+  }
+  let env_is_item = table
+    .get("env")
+    .and_then(toml::Value::as_table)
+    .is_some_and(|env| env.contains_key("cmd") || env.contains_key("command"));
+  if !env_is_item {
+    if let Some(env) = table.remove("env") {
+      defaults.insert(String::from("env"), env);
+    }
+  }
+  Ok((defaults.try_into()?, table.try_into()?))
+}
+
 #[derive(Deserialize)]
 pub struct RunFileItem {
   /// Optional. The runnable aliases. (aliases: `aliases`, `alias`)
@@ -43,6 +261,26 @@ pub struct RunFileItem {
   /// relative to the directory which contains the `runfile.toml`.
   #[serde(default = "default_path")]
   pub path: String,
+  /// Optional. When this item runs in another item's `after` chain,
+  /// keep going with the chain even if it fails. Default: false
+  #[serde(default)]
+  pub continue_on_error: bool,
+  /// Optional. Run the items in the `after` chain which don't depend on each other
+  /// at the same time. Default: false
+  #[serde(default)]
+  pub parallel: bool,
+  /// Optional. Values the command takes, substituted into `{{name}}` placeholders,
+  /// quoted for the `shell`.
+  #[serde(default)]
+  pub params: Vec<RunFileParam>,
+  /// Optional. The shell (or interpreter) to run the command with, called with `-c <command>`,
+  /// eg. `bash`, `zsh`, `fish` or `python3`. Default: the `shell` in the config file, or sh.
+  /// Extra args are passed to shells other than POSIX ones after the command, eg. in `sys.argv`.
+  pub shell: Option<String>,
+  /// Optional. The environment for the command,
+  /// applied over the top level `env` / `env_file` of the runfile.
+  #[serde(flatten)]
+  pub env: RunFileEnv,
 }
 
 fn default_path() -> String {
@@ -55,18 +293,31 @@ impl AddRunnables for RunFile {
   fn add_runnable(path: &Path, runnables: &mut Vec<Runnable>) -> anyhow::Result<()> {
     let file_path = path.join("runfile.toml");
     let contents = fs::read_to_string(file_path)?;
-    let contents: RunFileContent = toml::from_str(&contents)?;
-    runnables.extend(contents.into_iter().map(|(name, item)| Runnable {
-      name,
-      aliases: item.aliases,
-      display_name: None,
-      description: item.description,
-      after: item.after,
-      path: path.join(item.path),
-      index: 0,
-      params: RunnableParams::RunFile(RunFileParams {
-        command: item.command,
-      }),
+    let (defaults, contents) = parse_runfile(&contents)?;
+    runnables.extend(contents.into_iter().map(|(name, item)| {
+      let mut env = IndexMap::new();
+      let mut missing_env_files = Vec::new();
+      defaults.apply(path, &mut env, &mut missing_env_files);
+      item.env.apply(path, &mut env, &mut missing_env_files);
+      Runnable {
+        name,
+        aliases: item.aliases,
+        display_name: None,
+        description: item.description,
+        after: item.after,
+        path: path.join(item.path),
+        index: 0,
+        params: RunnableParams::RunFile(RunFileParams {
+          command: item.command,
+          continue_on_error: item.continue_on_error,
+          parallel: item.parallel,
+          params: item.params,
+          values: Default::default(),
+          env,
+          missing_env_files,
+          shell: item.shell,
+        }),
+      }
     }));
     Ok(())
   }
@@ -75,7 +326,135 @@ impl AddRunnables for RunFile {
 impl RunRunnable for RunFile {
   type Params = RunFileParams;
 
-  fn command(runnable: &Runnable, params: &Self::Params) -> String {
-    format!("cd {} && {}", runnable.path.display(), params.command)
+  fn invocation(runnable: &Runnable, params: &Self::Params) -> Invocation {
+    let shell = params.shell(config().shell.as_deref());
+    Invocation::shell(shell, params.command_line(shell))
+      .dir(&runnable.path)
+      .envs(&params.env)
+  }
+}
+
+#[cfg(test)]
+mod runfile_tests {
+  use super::{parse_runfile, RunFileContent};
+  use crate::{invocation::Invocation, sources::runfile::RunFileParams};
+
+  #[test]
+  fn resolve_and_substitute_params() {
+    let content: RunFileContent = toml::from_str(
+      r#"
+[deploy]
+cmd = "deploy --env {{env}} --port {{ port }} --dry-run={{dry_run}} --to {{target}}"
+params = [
+  { name = "env", choices = ["dev", "prod"] },
+  { name = "port", type = "int", default = 8080 },
+  { name = "dry_run", type = "bool", default = true },
+  { name = "target", type = "path", default = "my dir" },
+]
+"#,
+    )
+    .unwrap();
+    let item = &content["deploy"];
+    let params = || RunFileParams {
+      command: item.command.clone(),
+      params: item.params.clone(),
+      ..Default::default()
+    };
+
+    let error = params().resolve("deploy", &[]).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "missing value for required param 'env' of 'deploy', pass it with --param env=<value>"
+    );
+
+    let given = [(String::from("env"), String::from("staging"))];
+    let error = params().resolve("deploy", &given).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "invalid value for param 'env' of 'deploy': 'staging' is not one of dev, prod"
+    );
+
+    let given = [(String::from("env"), String::from("prod"))];
+    let mut missing_env_file = RunFileParams {
+      missing_env_files: vec![".env.missing".into()],
+      ..params()
+    };
+    let error = missing_env_file.resolve("deploy", &given).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "env_file of 'deploy' could not be read: .env.missing"
+    );
+
+    let mut resolved = params();
+    resolved.resolve("deploy", &given).unwrap();
+    assert_eq!(
+      resolved.command_line("sh"),
+      "deploy --env prod --port 8080 --dry-run=true --to 'my dir'"
+    );
+  }
+
+  #[test]
+  fn quote_values_for_the_shell() {
+    let mut params = RunFileParams {
+      command: String::from("print({{name}})"),
+      ..Default::default()
+    };
+    params
+      .values
+      .insert(String::from("name"), String::from(r#"it's a "test" \"#));
+    assert_eq!(
+      params.command_line("bash -eu"),
+      r#"print('it'\''s a "test" \')"#
+    );
+    assert_eq!(params.command_line("fish"), r#"print('it\'s a "test" \\')"#);
+    assert_eq!(
+      params.command_line("/usr/bin/env python3"),
+      r#"print("it's a \"test\" \\")"#
+    );
+
+    let output = Invocation::shell("python3", params.command_line("python3"))
+      .command()
+      .output()
+      .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+      String::from_utf8_lossy(&output.stdout),
+      "it's a \"test\" \\\n"
+    );
+  }
+
+  #[test]
+  fn parse_top_level_env() {
+    let (defaults, items) = parse_runfile(
+      r#"
+env_file = ".env"
+env = { RUST_LOG = "info" }
+
+[serve]
+cmd = "cargo run"
+env = { PORT = "8080" }
+env_file = [".env.local", ".env.dev"]
+"#,
+    )
+    .unwrap();
+    assert_eq!(defaults.env_file, [".env"]);
+    assert_eq!(defaults.env["RUST_LOG"], "info");
+    let serve = &items["serve"];
+    assert_eq!(serve.env.env["PORT"], "8080");
+    assert_eq!(serve.env.env_file, [".env.local", ".env.dev"]);
+
+    // An item can still be named env.
+    let (defaults, items) = parse_runfile("[env]\ncmd = \"env\"\n").unwrap();
+    assert!(defaults.env.is_empty());
+    assert_eq!(items["env"].command, "env");
+  }
+
+  #[test]
+  fn item_shell_then_configured_then_sh() {
+    let mut params = RunFileParams::default();
+    assert_eq!(params.shell(None), "sh");
+    assert_eq!(params.shell(Some("python3")), "python3");
+    params.shell = Some(String::from("bash"));
+    assert_eq!(params.shell(Some("python3")), "bash");
   }
 }
@@ -1,16 +1,117 @@
-use std::{fmt::Display, fs, path::Path};
+use std::{
+  fmt::Display,
+  fs,
+  path::{Path, PathBuf},
+};
 
 use anyhow::anyhow;
-use serde::Deserialize;
 
 use crate::{
+  invocation::Invocation,
   runnables::{AddRunnables, RunRunnable},
+  sources::{
+    cargo_metadata::CargoPackage,
+    cargo_options::CargoOptions,
+    cargo_toml::{CargoAction, CargoToml, CargoTomlTarget},
+  },
   types::{Runnable, RunnableParams},
 };
 
 #[derive(Debug, Clone, Default)]
 pub struct RustBinParams {
+  pub target: RustBinTarget,
   pub command: RustBinCommand,
+  /// Set when discovered through `cargo metadata`,
+  /// the commands then run from the workspace root with `-p <package>`.
+  pub package: Option<CargoPackage>,
+  /// Features the target needs to be built, passed with `--features`.
+  pub required_features: Vec<String>,
+  /// Features / profile picked before running.
+  pub options: CargoOptions,
+  /// Custom actions declared in `[package.metadata.runnables]`.
+  pub actions: Vec<CargoAction>,
+  /// The custom action to run instead of the command.
+  pub action: Option<CargoAction>,
+}
+
+impl RustBinParams {
+  /// The cargo command to run,
+  /// selecting the target named `name` where the command supports it.
+  pub fn invocation(&self, name: &str) -> Invocation {
+    if let Some(action) = &self.action {
+      return Invocation::shell("sh", &action.command);
+    }
+    let mut invocation = match (self.target, self.command) {
+      (RustBinTarget::Bench, RustBinCommand::Run | RustBinCommand::RunRelease) => {
+        Invocation::words("cargo bench")
+      }
+      (RustBinTarget::Test, RustBinCommand::Run | RustBinCommand::RunRelease) => {
+        Invocation::words("cargo test")
+      }
+      (_, RustBinCommand::Install) => match &self.package {
+        Some(package) => {
+          Invocation::words("cargo install --path").arg(package.path.display().to_string())
+        }
+        None => Invocation::words(&self.command.to_string()),
+      },
+      // The picked profile replaces `--release`, cargo rejects using both.
+      (_, RustBinCommand::RunRelease) if self.options.profile.is_some() => {
+        Invocation::words("cargo run")
+      }
+      (_, RustBinCommand::BuildRelease) if self.options.profile.is_some() => {
+        Invocation::words("cargo build")
+      }
+      (_, command) => Invocation::words(&command.to_string()),
+    };
+    if let Some(package) = &self.package {
+      if !matches!(self.command, RustBinCommand::Install) {
+        invocation = invocation.args(["-p", &package.name]);
+      }
+    }
+    // `cargo test` runs every target, so only the picked options apply.
+    if matches!(self.command, RustBinCommand::Test) {
+      invocation = invocation.args(self.options.args());
+    } else if self.takes_options() {
+      invocation = invocation.args([self.target.flag(), name]);
+      let mut options = self.options.clone();
+      options
+        .features
+        .extend(self.required_features.iter().cloned());
+      invocation = invocation.args(options.args());
+    }
+    invocation
+  }
+
+  /// Whether the command takes the features / profile from the picker.
+  pub fn takes_options(&self) -> bool {
+    self.action.is_none() && !matches!(self.command, RustBinCommand::Publish | RustBinCommand::Fmt)
+  }
+
+  /// The command line shown before running.
+  pub fn command_line(&self, name: &str) -> String {
+    self.invocation(name).to_string()
+  }
+}
+
+/// The kind of cargo target the runnable launches.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum RustBinTarget {
+  #[default]
+  Bin,
+  Example,
+  Test,
+  Bench,
+}
+
+impl RustBinTarget {
+  fn flag(self) -> &'static str {
+    match self {
+      RustBinTarget::Bin => "--bin",
+      RustBinTarget::Example => "--example",
+      RustBinTarget::Test => "--test",
+      RustBinTarget::Bench => "--bench",
+    }
+  }
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -46,17 +147,6 @@ impl Display for RustBinCommand {
   }
 }
 
-#[derive(Deserialize)]
-struct CargoToml {
-  package: CargoTomlPackage,
-}
-
-#[derive(Deserialize)]
-struct CargoTomlPackage {
-  name: String,
-  description: Option<String>,
-}
-
 pub struct RustBin;
 
 impl AddRunnables for RustBin {
@@ -65,22 +155,65 @@ impl AddRunnables for RustBin {
     if !metadata.is_dir() {
       return Err(anyhow!("path is not directory"));
     }
-    let cargo_toml_contents = fs::read_to_string(path.join("Cargo.toml"))?;
     let CargoToml {
-      package: CargoTomlPackage { name, description },
-    } = toml::from_str(&cargo_toml_contents)?;
+      package,
+      bin,
+      example,
+      bench,
+      ..
+    } = CargoToml::read(path)?;
+    let Some(package) = package else {
+      return Err(anyhow!("Cargo.toml has no package"));
+    };
+    let description = package.description();
+    let actions = package.metadata.runnables.actions.clone();
+
+    let mut inferred_bins = Vec::new();
+    if package.autobins {
+      if path.join("src/main.rs").is_file() {
+        inferred_bins.push((package.name.clone(), PathBuf::from("src/main.rs")));
+      }
+      inferred_bins.extend(infer_targets(path, "src/bin"));
+    }
+    let inferred_examples = if package.autoexamples {
+      infer_targets(path, "examples")
+    } else {
+      Vec::new()
+    };
+    let inferred_benches = if package.autobenches {
+      infer_targets(path, "benches")
+    } else {
+      Vec::new()
+    };
 
-    if let Ok(bin) = fs::metadata(path.join("src/main.rs")) {
-      if bin.is_file() {
+    let targets = [
+      (RustBinTarget::Bin, bin, inferred_bins),
+      (RustBinTarget::Example, example, inferred_examples),
+      (RustBinTarget::Bench, bench, inferred_benches),
+    ];
+    for (target, explicit, inferred) in targets {
+      for name in merge_targets(explicit, inferred) {
+        let (display_name, description) = match target {
+          RustBinTarget::Bin if name == package.name => (None, description.clone()),
+          RustBinTarget::Bin => (
+            Some(format!("{} / {name}", package.name)),
+            description.clone(),
+          ),
+          _ => (Some(format!("{} / {name}", package.name)), None),
+        };
         runnables.push(Runnable {
-          name: name.clone(),
+          name,
           aliases: Default::default(),
-          display_name: None,
-          description: description.clone(),
+          display_name,
+          description,
           after: None,
           path: path.to_owned(),
           index: 0,
-          params: RunnableParams::RustBin(Default::default()),
+          params: RunnableParams::RustBin(RustBinParams {
+            target,
+            actions: actions.clone(),
+            ..Default::default()
+          }),
         })
       }
     }
@@ -92,15 +225,132 @@ impl AddRunnables for RustBin {
 impl RunRunnable for RustBin {
   type Params = RustBinParams;
 
-  fn command(runnable: &Runnable, params: &Self::Params) -> String {
-    format!("cd {} && {}", runnable.path.display(), params.command)
+  fn invocation(runnable: &Runnable, params: &Self::Params) -> Invocation {
+    params.invocation(&runnable.name).dir(CargoPackage::run_dir(
+      &params.package,
+      &params.action,
+      &runnable.path,
+    ))
   }
+  fn separate_args(params: &Self::Params) -> bool {
+    params.action.is_none()
+      && matches!(
+        params.command,
+        RustBinCommand::Run | RustBinCommand::RunRelease | RustBinCommand::Test
+      )
+  }
+}
+
+/// Infers target names from `dir/*.rs` and `dir/*/main.rs`, like cargo's auto discovery.
+fn infer_targets(path: &Path, dir: &str) -> Vec<(String, PathBuf)> {
+  let Ok(entries) = fs::read_dir(path.join(dir)) else {
+    return Vec::new();
+  };
+  let mut targets = entries
+    .flatten()
+    .filter_map(|entry| {
+      let entry_path = entry.path();
+      let (name, file) = if entry_path.is_dir() {
+        (entry_path.file_name()?, entry_path.join("main.rs"))
+      } else if entry_path.extension().is_some_and(|ext| ext == "rs") {
+        (entry_path.file_stem()?, entry_path.clone())
+      } else {
+        return None;
+      };
+      if !file.is_file() {
+        return None;
+      }
+      let relative = file.strip_prefix(path).ok()?.to_path_buf();
+      Some((name.to_string_lossy().to_string(), relative))
+    })
+    .collect::<Vec<_>>();
+  targets.sort();
+  targets
+}
+
+/// Explicitly declared targets come first.
+/// Inferred targets are dropped if an explicit target uses the same name or path.
+fn merge_targets(explicit: Vec<CargoTomlTarget>, inferred: Vec<(String, PathBuf)>) -> Vec<String> {
+  let mut names = Vec::new();
+  let mut paths = Vec::new();
+  for target in explicit {
+    paths.extend(target.path);
+    names.push(target.name);
+  }
+  for (name, path) in inferred {
+    if !names.contains(&name) && !paths.contains(&path) {
+      names.push(name);
+    }
+  }
+  names
 }
 
 #[cfg(test)]
 mod rust_tests {
+  use std::path::{Path, PathBuf};
+
+  use super::{RustBin, RustBinCommand, RustBinParams};
+  use crate::{
+    runnables::RunRunnable,
+    sources::{cargo_metadata::CargoPackage, cargo_options::CargoOptions},
+    types::{Runnable, RunnableParams},
+  };
+
   #[test]
   fn run_test() {
     assert_eq!("it works", "it works")
   }
+
+  #[test]
+  fn plan_invocation_with_spaces_in_paths() {
+    let params = RustBinParams {
+      command: RustBinCommand::Install,
+      package: Some(CargoPackage {
+        name: String::from("my-cli"),
+        path: PathBuf::from("crates/my cli"),
+      }),
+      ..Default::default()
+    };
+    let runnable = Runnable {
+      name: String::from("my-cli"),
+      path: PathBuf::from("/tmp/$work space"),
+      params: RunnableParams::RustBin(params.clone()),
+      ..Default::default()
+    };
+    let invocation = RustBin::invocation(&runnable, &params);
+    assert_eq!(invocation.program, "cargo");
+    assert_eq!(
+      invocation.args,
+      ["install", "--path", "crates/my cli", "--bin", "my-cli"]
+    );
+    assert_eq!(
+      invocation.dir.as_deref(),
+      Some(Path::new("/tmp/$work space"))
+    );
+  }
+
+  #[test]
+  fn test_invocation_keeps_picked_options() {
+    let params = RustBinParams {
+      command: RustBinCommand::Test,
+      required_features: vec![String::from("cli")],
+      options: CargoOptions {
+        features: vec![String::from("serde"), String::from("tracing")],
+        no_default_features: true,
+        profile: Some(String::from("ci")),
+      },
+      ..Default::default()
+    };
+    assert_eq!(
+      params.invocation("my-cli").args,
+      [
+        "test",
+        "--features",
+        "serde,tracing",
+        "--no-default-features",
+        "--profile",
+        "ci"
+      ]
+    );
+  }
 }
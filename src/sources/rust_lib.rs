@@ -1,16 +1,62 @@
 use std::{fmt::Display, fs, path::Path};
 
 use anyhow::anyhow;
-use serde::Deserialize;
 
 use crate::{
+  invocation::Invocation,
   runnables::{AddRunnables, RunRunnable},
+  sources::{
+    cargo_metadata::CargoPackage,
+    cargo_options::CargoOptions,
+    cargo_toml::{CargoAction, CargoToml},
+  },
   types::{Runnable, RunnableParams},
 };
 
 #[derive(Debug, Clone, Default)]
 pub struct RustLibParams {
   pub command: RustLibCommand,
+  /// Set when discovered through `cargo metadata`,
+  /// the commands then run from the workspace root with `-p <package>`.
+  pub package: Option<CargoPackage>,
+  pub proc_macro: bool,
+  /// Features / profile picked before running.
+  pub options: CargoOptions,
+  /// Custom actions declared in `[package.metadata.runnables]`.
+  pub actions: Vec<CargoAction>,
+  /// The custom action to run instead of the command.
+  pub action: Option<CargoAction>,
+}
+
+impl RustLibParams {
+  pub fn invocation(&self) -> Invocation {
+    if let Some(action) = &self.action {
+      return Invocation::shell("sh", &action.command);
+    }
+    let command = match self.command {
+      // The picked profile replaces `--release`, cargo rejects using both.
+      RustLibCommand::BuildRelease if self.options.profile.is_some() => RustLibCommand::Build,
+      command => command,
+    };
+    let mut invocation = Invocation::words(&command.to_string());
+    if let Some(package) = &self.package {
+      invocation = invocation.args(["-p", &package.name]);
+    }
+    if self.takes_options() {
+      invocation = invocation.args(self.options.args());
+    }
+    invocation
+  }
+
+  /// Whether the command takes the features / profile from the picker.
+  pub fn takes_options(&self) -> bool {
+    self.action.is_none() && !matches!(self.command, RustLibCommand::Publish | RustLibCommand::Fmt)
+  }
+
+  /// The command line shown before running.
+  pub fn command_line(&self) -> String {
+    self.invocation().to_string()
+  }
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -40,17 +86,6 @@ impl Display for RustLibCommand {
   }
 }
 
-#[derive(Deserialize)]
-struct CargoToml {
-  package: CargoTomlPackage,
-}
-
-#[derive(Deserialize)]
-struct CargoTomlPackage {
-  name: String,
-  description: Option<String>,
-}
-
 pub struct RustLib;
 
 impl AddRunnables for RustLib {
@@ -59,22 +94,24 @@ impl AddRunnables for RustLib {
     if !metadata.is_dir() {
       return Err(anyhow!("path is not directory"));
     }
-    let cargo_toml_contents = fs::read_to_string(path.join("Cargo.toml"))?;
-    let CargoToml {
-      package: CargoTomlPackage { name, description },
-    } = toml::from_str(&cargo_toml_contents)?;
+    let Some(package) = CargoToml::read(path)?.package else {
+      return Err(anyhow!("Cargo.toml has no package"));
+    };
 
     if let Ok(lib) = fs::metadata(path.join("src/lib.rs")) {
       if lib.is_file() {
         runnables.push(Runnable {
-          name: name.clone(),
+          description: package.description(),
+          name: package.name,
           aliases: Default::default(),
           display_name: None,
-          description: description.clone(),
           after: None,
           path: path.to_owned(),
           index: 0,
-          params: RunnableParams::RustLib(RustLibParams::default()),
+          params: RunnableParams::RustLib(RustLibParams {
+            actions: package.metadata.runnables.actions,
+            ..Default::default()
+          }),
         })
       }
     }
@@ -86,7 +123,11 @@ impl AddRunnables for RustLib {
 impl RunRunnable for RustLib {
   type Params = RustLibParams;
 
-  fn command(runnable: &Runnable, params: &Self::Params) -> String {
-    format!("cd {} && {}", runnable.path.display(), params.command)
+  fn invocation(runnable: &Runnable, params: &Self::Params) -> Invocation {
+    params.invocation().dir(CargoPackage::run_dir(
+      &params.package,
+      &params.action,
+      &runnable.path,
+    ))
   }
 }
@@ -0,0 +1,81 @@
+use std::{fmt::Display, path::Path};
+
+use anyhow::anyhow;
+
+use crate::{
+  invocation::Invocation,
+  runnables::{AddRunnables, RunRunnable},
+  sources::cargo_toml::CargoToml,
+  types::{Runnable, RunnableParams},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct RustWorkspaceParams {
+  pub command: RustWorkspaceCommand,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub enum RustWorkspaceCommand {
+  #[default]
+  Build,
+  BuildRelease,
+  Test,
+  Fmt,
+  Check,
+  Clippy,
+}
+
+impl Display for RustWorkspaceCommand {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let d = match self {
+      RustWorkspaceCommand::Build => "cargo build --workspace",
+      RustWorkspaceCommand::BuildRelease => "cargo build --workspace --release",
+      RustWorkspaceCommand::Test => "cargo test --workspace",
+      RustWorkspaceCommand::Fmt => "cargo fmt --all",
+      RustWorkspaceCommand::Check => "cargo check --workspace",
+      RustWorkspaceCommand::Clippy => "cargo clippy --workspace",
+    };
+    f.write_str(d)
+  }
+}
+
+pub struct RustWorkspace;
+
+impl AddRunnables for RustWorkspace {
+  fn add_runnable(path: &Path, runnables: &mut Vec<Runnable>) -> anyhow::Result<()> {
+    let cargo_toml = CargoToml::read(path)?;
+    let Some(workspace) = cargo_toml.workspace else {
+      return Err(anyhow!("Cargo.toml is not a workspace root"));
+    };
+    let name = match &cargo_toml.package {
+      Some(package) => package.name.clone(),
+      None => path
+        .canonicalize()?
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| String::from("workspace")),
+    };
+    runnables.push(Runnable {
+      name,
+      aliases: Default::default(),
+      display_name: None,
+      description: workspace
+        .package
+        .description
+        .or_else(|| Some(format!("members: {}", workspace.members.join(", ")))),
+      after: None,
+      path: path.to_owned(),
+      index: 0,
+      params: RunnableParams::RustWorkspace(Default::default()),
+    });
+    Ok(())
+  }
+}
+
+impl RunRunnable for RustWorkspace {
+  type Params = RustWorkspaceParams;
+
+  fn invocation(runnable: &Runnable, params: &Self::Params) -> Invocation {
+    Invocation::words(&params.command.to_string()).dir(&runnable.path)
+  }
+}
@@ -1,14 +1,22 @@
-use std::{fs, path::Path};
+use std::{
+  fs,
+  io::{BufRead, BufReader},
+  path::Path,
+};
 
 use anyhow::anyhow;
 
 use crate::{
+  invocation::Invocation,
   runnables::{AddRunnables, RunRunnable},
   types::{Runnable, RunnableParams},
 };
 
 #[derive(Debug, Clone, Default)]
-pub struct ShellParams {}
+pub struct ShellParams {
+  /// The interpreter from the script's `#!` line, eg. `/bin/bash` or `/usr/bin/env python3`.
+  pub shebang: Option<String>,
+}
 
 pub struct Shell;
 
@@ -41,7 +49,9 @@ impl AddRunnables for Shell {
           path: child.path(),
           after: None,
           index: 0,
-          params: RunnableParams::Shell(ShellParams {}),
+          params: RunnableParams::Shell(ShellParams {
+            shebang: shebang(&child.path()),
+          }),
           description: Default::default(),
         })
       }
@@ -53,7 +63,56 @@ impl AddRunnables for Shell {
 impl RunRunnable for Shell {
   type Params = ShellParams;
 
-  fn command(runnable: &Runnable, _: &Self::Params) -> String {
-    format!("sh {}", runnable.path.display())
+  fn invocation(runnable: &Runnable, params: &Self::Params) -> Invocation {
+    Invocation::words(interpreter(params.shebang.as_deref()))
+      .arg(runnable.path.display().to_string())
+  }
+}
+
+/// The interpreter from the `#!` line, or sh.
+/// The `shell` in the config file is only meant for runfile items,
+/// a `.sh` script without a shebang is still a sh script.
+fn interpreter(shebang: Option<&str>) -> &str {
+  shebang.unwrap_or("sh")
+}
+
+/// Reads the interpreter from the `#!` line at the top of the script.
+fn shebang(path: &Path) -> Option<String> {
+  let file = fs::File::open(path).ok()?;
+  let mut line = String::new();
+  BufReader::new(file).read_line(&mut line).ok()?;
+  let interpreter = line.strip_prefix("#!")?.trim();
+  (!interpreter.is_empty()).then(|| interpreter.to_string())
+}
+
+#[cfg(test)]
+mod shell_tests {
+  use super::{interpreter, shebang};
+  use crate::helpers::TestDir;
+
+  #[test]
+  fn interpreter_from_shebang() {
+    let dir = TestDir::new("shell");
+    let scripts = [
+      ("env.sh", "#!/usr/bin/env python3\nprint('hi')\n"),
+      ("bash.sh", "#! /bin/bash \necho hi\n"),
+      ("plain.sh", "echo hi\n"),
+      ("empty.sh", "#!\n"),
+    ];
+    let shebangs = scripts
+      .iter()
+      .map(|(name, contents)| shebang(&dir.write(name, contents)))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      shebangs,
+      [
+        Some(String::from("/usr/bin/env python3")),
+        Some(String::from("/bin/bash")),
+        None,
+        None
+      ]
+    );
+    assert_eq!(interpreter(Some("/bin/bash")), "/bin/bash");
+    assert_eq!(interpreter(None), "sh");
   }
 }
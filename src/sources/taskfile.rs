@@ -0,0 +1,272 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Context};
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{
+  invocation::Invocation,
+  runnables::{AddRunnables, RunRunnable},
+  types::{Runnable, RunnableParams},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct TaskfileParams {}
+
+const TASKFILES: &[&str] = &[
+  "Taskfile.yml",
+  "taskfile.yml",
+  "Taskfile.yaml",
+  "taskfile.yaml",
+  "Taskfile.dist.yml",
+  "taskfile.dist.yml",
+  "Taskfile.dist.yaml",
+  "taskfile.dist.yaml",
+];
+
+#[derive(Deserialize)]
+struct TaskfileYaml {
+  #[serde(default)]
+  includes: IndexMap<String, TaskfileInclude>,
+  #[serde(default)]
+  tasks: IndexMap<String, serde_yaml_ng::Value>,
+}
+
+/// Another Taskfile whose tasks are run as `<namespace>:<task>`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TaskfileInclude {
+  Path(String),
+  Definition {
+    taskfile: String,
+    #[serde(default)]
+    internal: bool,
+    /// The tasks are run without the namespace.
+    #[serde(default)]
+    flatten: bool,
+  },
+}
+
+#[derive(Deserialize, Default)]
+struct TaskfileTaskDefinition {
+  desc: Option<String>,
+  summary: Option<String>,
+  #[serde(default)]
+  aliases: Vec<String>,
+  #[serde(default)]
+  deps: Vec<TaskfileDep>,
+  #[serde(default)]
+  internal: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TaskfileDep {
+  Name(String),
+  Task { task: String },
+}
+
+/// How deep includes are followed, in case they include each other.
+const MAX_INCLUDE_DEPTH: usize = 5;
+
+pub struct Taskfile;
+
+impl AddRunnables for Taskfile {
+  fn add_runnable(path: &Path, runnables: &mut Vec<Runnable>) -> anyhow::Result<()> {
+    let contents =
+      read_taskfile(path).ok_or_else(|| anyhow!("directory does not include Taskfile.yml"))?;
+    add_tasks(&contents, path, path, "", 0, runnables)
+  }
+}
+
+/// The Taskfile in the directory.
+fn read_taskfile(dir: &Path) -> Option<String> {
+  TASKFILES
+    .iter()
+    .find_map(|taskfile| fs::read_to_string(dir.join(taskfile)).ok())
+}
+
+/// Adds the tasks of the Taskfile in `dir` with their names prefixed by the `namespace`,
+/// then the tasks of its includes. They all run from `path`, the directory of the root Taskfile.
+fn add_tasks(
+  contents: &str,
+  dir: &Path,
+  path: &Path,
+  namespace: &str,
+  depth: usize,
+  runnables: &mut Vec<Runnable>,
+) -> anyhow::Result<()> {
+  let TaskfileYaml { includes, tasks } =
+    serde_yaml_ng::from_str(contents).context("failed to parse Taskfile.yml")?;
+  for (name, task) in tasks {
+    // Tasks can also use the short form, eg. `build: go build ./...`
+    let task = if task.is_mapping() {
+      serde_yaml_ng::from_value::<TaskfileTaskDefinition>(task)
+        .with_context(|| format!("failed to parse task {name}"))?
+    } else {
+      Default::default()
+    };
+    if task.internal {
+      continue;
+    }
+    let after = task
+      .deps
+      .into_iter()
+      .map(|dep| match dep {
+        TaskfileDep::Name(task) | TaskfileDep::Task { task } => task,
+      })
+      // Skip templated deps, they can't be resolved without evaluating the Taskfile.
+      .filter(|task| !task.contains("{{"))
+      // A leading `:` refers to a task of the root Taskfile.
+      .map(|task| match task.strip_prefix(':') {
+        Some(task) => format!("Taskfile:{task}"),
+        None => format!("Taskfile:{namespace}{task}"),
+      })
+      .collect::<Vec<_>>();
+    runnables.push(Runnable {
+      name: format!("{namespace}{name}"),
+      aliases: task
+        .aliases
+        .into_iter()
+        .map(|alias| format!("{namespace}{alias}"))
+        .collect(),
+      display_name: None,
+      description: task.desc.or(task.summary),
+      after: (!after.is_empty()).then_some(after),
+      path: path.to_owned(),
+      index: 0,
+      params: RunnableParams::Taskfile(TaskfileParams {}),
+    });
+  }
+  if depth == MAX_INCLUDE_DEPTH {
+    return Ok(());
+  }
+  for (include_namespace, include) in includes {
+    let (taskfile, flatten) = match include {
+      TaskfileInclude::Path(taskfile) => (taskfile, false),
+      TaskfileInclude::Definition { internal: true, .. } => continue,
+      TaskfileInclude::Definition {
+        taskfile, flatten, ..
+      } => (taskfile, flatten),
+    };
+    if taskfile.contains("{{") {
+      continue;
+    }
+    // The include is either a Taskfile, or a directory with one.
+    let taskfile = dir.join(taskfile);
+    let (include_dir, contents) = if taskfile.is_dir() {
+      (taskfile.clone(), read_taskfile(&taskfile))
+    } else {
+      let include_dir = taskfile.parent().unwrap_or(dir).to_path_buf();
+      (include_dir, fs::read_to_string(&taskfile).ok())
+    };
+    // Missing (eg. optional) includes are skipped, task reports them when run.
+    let Some(contents) = contents else {
+      continue;
+    };
+    let namespace = if flatten {
+      namespace.to_string()
+    } else {
+      format!("{namespace}{include_namespace}:")
+    };
+    add_tasks(
+      &contents,
+      &include_dir,
+      path,
+      &namespace,
+      depth + 1,
+      runnables,
+    )
+    .with_context(|| format!("in the Taskfile included as {include_namespace}"))?;
+  }
+  Ok(())
+}
+
+impl RunRunnable for Taskfile {
+  type Params = TaskfileParams;
+
+  fn invocation(runnable: &Runnable, _: &Self::Params) -> Invocation {
+    Invocation::new("task")
+      .arg(&runnable.name)
+      .dir(&runnable.path)
+  }
+  /// Task reads the args after `--` as `{{.CLI_ARGS}}`, without it they are more task names.
+  fn separate_args(_: &Self::Params) -> bool {
+    true
+  }
+}
+
+#[cfg(test)]
+mod taskfile_tests {
+  use super::Taskfile;
+  use crate::{helpers::TestDir, runnables::AddRunnables};
+
+  #[test]
+  fn parse_tasks_and_includes() {
+    let dir = TestDir::new("taskfile");
+    dir.write(
+      "Taskfile.yml",
+      r#"
+version: '3'
+includes:
+  docs: ./docs
+  secret:
+    taskfile: ./docs/Taskfile.yml
+    internal: true
+tasks:
+  build:
+    desc: Build the app
+    aliases: [b]
+    deps: [generate, docs:build]
+  generate: go generate ./...
+  setup:
+    internal: true
+    cmds: [go mod download]
+"#,
+    );
+    dir.write(
+      "docs/Taskfile.yml",
+      r#"
+version: '3'
+tasks:
+  build:
+    summary: Build the docs
+    deps: [":generate", lint]
+  lint: markdownlint .
+"#,
+    );
+    let mut runnables = Vec::new();
+    Taskfile::add_runnable(&dir, &mut runnables).unwrap();
+
+    let names = runnables
+      .iter()
+      .map(|runnable| runnable.name.as_str())
+      .collect::<Vec<_>>();
+    assert_eq!(names, ["build", "generate", "docs:build", "docs:lint"]);
+    let build = &runnables[0];
+    assert_eq!(build.description.as_deref(), Some("Build the app"));
+    assert_eq!(build.aliases, ["b"]);
+    assert_eq!(
+      build.after.as_deref(),
+      Some(
+        &[
+          String::from("Taskfile:generate"),
+          String::from("Taskfile:docs:build")
+        ][..]
+      )
+    );
+    let docs_build = &runnables[2];
+    assert_eq!(docs_build.description.as_deref(), Some("Build the docs"));
+    assert_eq!(
+      docs_build.after.as_deref(),
+      Some(
+        &[
+          String::from("Taskfile:generate"),
+          String::from("Taskfile:docs:lint")
+        ][..]
+      )
+    );
+    // Included tasks run from the root Taskfile.
+    assert!(runnables.iter().all(|runnable| runnable.path == *dir));
+  }
+}
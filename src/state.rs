@@ -1,15 +1,30 @@
-use std::rc::Rc;
+use std::{cmp::Reverse, rc::Rc, sync::Arc, time::Duration};
 
+use anyhow::anyhow;
 use clap::Parser;
-use ratatui::crossterm::event::{Event, KeyCode};
+use indexmap::IndexMap;
+use ratatui::{
+  crossterm::event::{Event, KeyCode},
+  widgets::TableState,
+};
 use tui_input::{backend::crossterm::EventHandler, Input};
 
 use crate::{
-  helpers::{absolute_path, split_match_strings},
+  fuzzy::fuzzy_match,
+  graph::{RunnableGraph, Step},
+  helpers::{absolute_path, strip_ansi},
+  jobs::{Job, JobPlan, JobStatus},
+  runnables::RunOutcome,
   sources::{
+    cargo_options::{CargoOptions, CargoOptionsAvailable},
     get_runnables,
-    rust_bin::{RustBinCommand, RustBinParams},
+    go::{GoCommand, GoParams},
+    javascript::{JavascriptCommand, JavascriptParams},
+    python::{PythonCommand, PythonParams, PythonTarget},
+    runfile::{RunFileParam, RunFileParamType},
+    rust_bin::{RustBinCommand, RustBinParams, RustBinTarget},
     rust_lib::{RustLibCommand, RustLibParams},
+    rust_workspace::{RustWorkspaceCommand, RustWorkspaceParams},
   },
   types::{Runnable, RunnableParams},
   CliArgs,
@@ -19,16 +34,374 @@ use crate::{
 pub enum Mode {
   List,
   Search,
+  CargoOptions,
+  Params,
+  Output,
 }
 
 pub struct State {
   pub args: CliArgs,
   pub runnables: Vec<Rc<Runnable>>,
+  /// The runnables matching the search, best match first.
   pub active: Vec<Rc<Runnable>>,
+  /// The matched characters in the displayed name of each active runnable.
+  pub highlights: Vec<Vec<usize>>,
   pub selected: usize,
   pub runnable: Runnable,
   pub mode: Mode,
   pub search: Input,
+  /// Whether to open the feature / profile picker before running rust runnables.
+  pub pick_cargo_options: bool,
+  pub cargo_options: Option<CargoOptionsPicker>,
+  pub params_form: Option<ParamsForm>,
+  /// Whether to come back to the interface after running.
+  pub stay: bool,
+  pub last_run: Option<LastRun>,
+  /// Keeps the scroll position of the list between frames, and between runs.
+  pub table: TableState,
+  /// Whether to run runnables in the output pane instead of the terminal.
+  pub embed: bool,
+  pub output: OutputPane,
+}
+
+/// The output pane, with a tab for each runnable started in the interface.
+#[derive(Default)]
+pub struct OutputPane {
+  pub tabs: Vec<OutputTab>,
+  pub selected: usize,
+  /// Search within the output of the selected tab.
+  pub search: Input,
+  /// Whether the search is being typed.
+  pub searching: bool,
+  /// The number of lines which fit in the pane, from the last render.
+  pub height: usize,
+}
+
+pub struct OutputTab {
+  pub job: Arc<Job>,
+  /// The first line shown, or None to follow the end of the output.
+  pub scroll: Option<usize>,
+}
+
+impl OutputPane {
+  pub fn tab(&self) -> Option<&OutputTab> {
+    self.tabs.get(self.selected)
+  }
+
+  /// The status of the latest run of the runnable, by its index.
+  pub fn status(&self, index: usize) -> Option<JobStatus> {
+    self
+      .tabs
+      .iter()
+      .find(|tab| tab.job.plan.runnable.index == index)
+      .map(|tab| tab.job.status())
+  }
+
+  /// The number of the first line shown for the tab.
+  pub fn top(&self, tab: &OutputTab) -> usize {
+    let (first, end) = self.scroll_range(tab);
+    tab.scroll.map_or(end, |scroll| scroll.clamp(first, end))
+  }
+
+  /// The lowest and highest top line, the highest showing the end of the output.
+  fn scroll_range(&self, tab: &OutputTab) -> (usize, usize) {
+    let lines = tab.job.lines();
+    let end = lines.end().saturating_sub(self.height);
+    (lines.first(), end.max(lines.first()))
+  }
+
+  /// Shows the job, in place of a finished run of the same runnable.
+  fn open(&mut self, job: Arc<Job>) {
+    let previous = self.tabs.iter().position(|tab| {
+      tab.job.plan.runnable.index == job.plan.runnable.index
+        && tab.job.status() != JobStatus::Running
+    });
+    let tab = OutputTab { job, scroll: None };
+    match previous {
+      Some(previous) => {
+        self.tabs[previous] = tab;
+        self.selected = previous;
+      }
+      None => {
+        self.tabs.push(tab);
+        self.selected = self.tabs.len() - 1;
+      }
+    }
+  }
+
+  fn scroll(&mut self, lines: isize) {
+    let Some(tab) = self.tabs.get(self.selected) else {
+      return;
+    };
+    let (first, end) = self.scroll_range(tab);
+    let top = self.top(tab).saturating_add_signed(lines).clamp(first, end);
+    // Scrolling back to the end follows the output again.
+    self.tabs[self.selected].scroll = (top < end).then_some(top);
+  }
+
+  fn scroll_to(&mut self, line: Option<usize>) {
+    if let Some(tab) = self.tabs.get_mut(self.selected) {
+      tab.scroll = line;
+    }
+  }
+
+  /// Scrolls to the next (or previous) line matching the search,
+  /// starting after the current top line if `skip_current`.
+  fn find(&mut self, forward: bool, skip_current: bool) {
+    let query = self.search.value().to_ascii_lowercase();
+    let Some(tab) = self.tab() else {
+      return;
+    };
+    if query.is_empty() {
+      return;
+    }
+    let top = self.top(tab);
+    let found = {
+      let lines = tab.job.lines();
+      let (first, len) = (lines.first(), lines.end() - lines.first());
+      let top = top - first;
+      let skip = usize::from(skip_current);
+      (0..len)
+        .map(|i| {
+          if forward {
+            first + (top + skip + i) % len
+          } else {
+            first + (top + 2 * len - skip - i) % len
+          }
+        })
+        .find(|&i| {
+          lines
+            .get(i)
+            .is_some_and(|line| strip_ansi(line).to_ascii_lowercase().contains(&query))
+        })
+    };
+    if let Some(line) = found {
+      self.scroll_to(Some(line));
+    }
+  }
+
+  fn select_prev(&mut self) {
+    if self.tabs.is_empty() {
+      return;
+    }
+    if self.selected == 0 {
+      self.selected = self.tabs.len() - 1;
+    } else {
+      self.selected -= 1;
+    }
+  }
+
+  fn select_next(&mut self) {
+    if self.tabs.is_empty() {
+      return;
+    }
+    self.selected += 1;
+    self.selected %= self.tabs.len();
+  }
+
+  fn restart(&mut self) {
+    let Some(tab) = self.tabs.get_mut(self.selected) else {
+      return;
+    };
+    tab.job.kill();
+    tab.job = Job::start_after(tab.job.plan.clone(), Some(tab.job.clone()));
+    tab.scroll = None;
+  }
+
+  fn close(&mut self) {
+    if self.selected >= self.tabs.len() {
+      return;
+    }
+    let tab = self.tabs.remove(self.selected);
+    tab.job.kill();
+    self.selected = self.selected.min(self.tabs.len().saturating_sub(1));
+  }
+
+  /// Kills the running jobs and waits for them to exit.
+  pub fn stop(&self) {
+    for tab in &self.tabs {
+      tab.job.kill();
+    }
+    for tab in &self.tabs {
+      while tab.job.status() == JobStatus::Running {
+        std::thread::sleep(Duration::from_millis(20));
+      }
+    }
+  }
+}
+
+/// How the previous run went, shown in the status bar when staying in the interface.
+pub struct LastRun {
+  pub name: String,
+  /// The error if it couldn't be started, eg. a cycle in `after`.
+  pub outcome: Result<RunOutcome, String>,
+  pub elapsed: Duration,
+}
+
+/// The feature / profile picker popup for the chosen rust runnable.
+pub struct CargoOptionsPicker {
+  pub available: CargoOptionsAvailable,
+  /// Whether each available feature is enabled.
+  pub features: Vec<bool>,
+  pub no_default_features: bool,
+  /// Index into the available profiles.
+  pub profile: Option<usize>,
+  /// The cursor, over the features, then `--no-default-features`, then the profiles.
+  pub selected: usize,
+}
+
+impl CargoOptionsPicker {
+  fn new(available: CargoOptionsAvailable) -> CargoOptionsPicker {
+    CargoOptionsPicker {
+      features: vec![false; available.features.len()],
+      available,
+      no_default_features: false,
+      profile: None,
+      selected: 0,
+    }
+  }
+
+  fn len(&self) -> usize {
+    self.features.len() + 1 + self.available.profiles.len()
+  }
+
+  fn select_prev(&mut self) {
+    if self.selected == 0 {
+      self.selected = self.len() - 1;
+    } else {
+      self.selected -= 1;
+    }
+  }
+
+  fn select_next(&mut self) {
+    self.selected += 1;
+    self.selected %= self.len();
+  }
+
+  fn toggle(&mut self) {
+    let features = self.features.len();
+    if let Some(enabled) = self.features.get_mut(self.selected) {
+      *enabled = !*enabled;
+    } else if self.selected == features {
+      self.no_default_features = !self.no_default_features;
+    } else {
+      let profile = self.selected - features - 1;
+      self.profile = if self.profile == Some(profile) {
+        None
+      } else {
+        Some(profile)
+      };
+    }
+  }
+
+  fn options(&self) -> CargoOptions {
+    CargoOptions {
+      features: self
+        .available
+        .features
+        .iter()
+        .zip(&self.features)
+        .filter(|(_, enabled)| **enabled)
+        .map(|(feature, _)| feature.clone())
+        .collect(),
+      no_default_features: self.no_default_features,
+      profile: self
+        .profile
+        .and_then(|profile| self.available.profiles.get(profile).cloned()),
+    }
+  }
+}
+
+/// The form to fill in the params of the chosen runfile item.
+pub struct ParamsForm {
+  pub params: Vec<RunFileParam>,
+  /// The input for each param.
+  pub inputs: Vec<Input>,
+  pub selected: usize,
+  /// Why the values were rejected on submit.
+  pub error: Option<String>,
+}
+
+impl ParamsForm {
+  /// Inputs are pre-filled with the `--param` value or the default.
+  fn new(params: Vec<RunFileParam>, given: &[(String, String)]) -> ParamsForm {
+    let inputs = params
+      .iter()
+      .map(|param| {
+        let value = given
+          .iter()
+          .rev()
+          .find(|(name, _)| *name == param.name)
+          .map(|(_, value)| value.clone())
+          .or_else(|| param.default_value())
+          .unwrap_or_default();
+        Input::new(value)
+      })
+      .collect();
+    ParamsForm {
+      params,
+      inputs,
+      selected: 0,
+      error: None,
+    }
+  }
+
+  fn select_prev(&mut self) {
+    if self.selected == 0 {
+      self.selected = self.inputs.len() - 1;
+    } else {
+      self.selected -= 1;
+    }
+  }
+
+  fn select_next(&mut self) {
+    self.selected += 1;
+    self.selected %= self.inputs.len();
+  }
+
+  /// Steps through the choices (or true / false) of the selected param.
+  /// Returns false if the param has nothing to step through.
+  fn cycle(&mut self, forward: bool) -> bool {
+    let param = &self.params[self.selected];
+    let options = match param.kind {
+      RunFileParamType::Bool if param.choices.is_empty() => {
+        vec![String::from("true"), String::from("false")]
+      }
+      _ => param.choices(),
+    };
+    if options.is_empty() {
+      return false;
+    }
+    let input = &mut self.inputs[self.selected];
+    let next = match options.iter().position(|option| option == input.value()) {
+      Some(current) if forward => (current + 1) % options.len(),
+      Some(0) => options.len() - 1,
+      Some(current) => current - 1,
+      None => 0,
+    };
+    *input = Input::new(options[next].clone());
+    true
+  }
+
+  /// The values by name, or the first invalid value.
+  fn values(&self) -> anyhow::Result<IndexMap<String, String>> {
+    self
+      .params
+      .iter()
+      .zip(&self.inputs)
+      .map(|(param, input)| {
+        let value = input.value().to_string();
+        // Like a missing --param, an empty input doesn't count as a value.
+        if value.is_empty() && param.default.is_none() {
+          return Err(anyhow!("{}: a value is required", param.name));
+        }
+        param
+          .validate(&value)
+          .map_err(|e| anyhow!("{}: {e}", param.name))?;
+        Ok((param.name.clone(), value))
+      })
+      .collect()
+  }
 }
 
 impl State {
@@ -49,25 +422,85 @@ impl State {
     let mut state = State {
       runnables: runnables.into_iter().map(Rc::new).collect(),
       active: Default::default(),
+      highlights: Default::default(),
       selected: 0,
       runnable: Default::default(),
       search: Input::with_value(Default::default(), args.search.clone().unwrap_or_default()),
       mode,
+      pick_cargo_options: args.cargo_options,
+      cargo_options: None,
+      params_form: None,
+      stay: args.stay,
+      last_run: None,
+      table: TableState::default(),
+      embed: args.embed,
+      output: Default::default(),
       args,
     };
     state.set_active_runnables();
     Ok(state)
   }
 
+  /// Fuzzy matches the search on the displayed name, name and aliases of the runnables,
+  /// ranking them by their best match. An empty search keeps them in discovery order.
   pub fn set_active_runnables(&mut self) {
-    self.active = self
+    let query = self.search.value();
+    let mut matches = self
       .runnables
       .iter()
-      .filter(|runnable| {
-        split_match_strings(self.search.value(), &runnable.name, &runnable.aliases())
+      .filter_map(|runnable| {
+        let displayed = runnable.display_name.as_deref().unwrap_or(&runnable.name);
+        // Only the displayed name is highlighted.
+        let highlighted = fuzzy_match(query, displayed);
+        let score = std::iter::once(runnable.name.as_str())
+          .chain(runnable.aliases())
+          .filter_map(|target| fuzzy_match(query, target))
+          .map(|found| found.score)
+          .chain(highlighted.as_ref().map(|found| found.score))
+          .max()?;
+        let positions = highlighted.map(|found| found.positions).unwrap_or_default();
+        Some((score, runnable.clone(), positions))
       })
-      .cloned()
-      .collect();
+      .collect::<Vec<_>>();
+    matches.sort_by_key(|(score, ..)| Reverse(*score));
+    (self.active, self.highlights) = matches
+      .into_iter()
+      .map(|(_, runnable, positions)| (runnable, positions))
+      .unzip();
+  }
+
+  /// The `after` chain of the chosen runnable and how many of its steps to run at once,
+  /// with the params of all of them resolved.
+  pub fn plan(&mut self) -> anyhow::Result<(Vec<Step>, usize)> {
+    let mut prerequisites = RunnableGraph::new(&self.runnables).prerequisites(&self.runnable)?;
+    self.runnable.resolve_params(&self.args.params)?;
+    for step in &mut prerequisites {
+      Rc::make_mut(&mut step.runnable).resolve_params(&self.args.params)?;
+    }
+    let jobs = match self.args.jobs {
+      Some(jobs) => jobs,
+      None if self.runnable.parallel() => prerequisites.len(),
+      None => 1,
+    };
+    Ok((prerequisites, jobs))
+  }
+
+  /// Starts the chosen runnable in a tab of the output pane.
+  pub fn start_job(&mut self) {
+    let job = match self.plan() {
+      Ok((prerequisites, jobs)) => Job::start(JobPlan::new(
+        self.runnable.clone(),
+        &prerequisites,
+        jobs,
+        &self.args.args,
+      )),
+      Err(e) => Job::failed(
+        JobPlan::new(self.runnable.clone(), &[], 1, &self.args.args),
+        &e,
+      ),
+    };
+    self.runnable = Default::default();
+    self.output.open(job);
   }
 
   pub fn root_absolute_path(&self) -> anyhow::Result<String> {
@@ -84,6 +517,9 @@ impl State {
     match self.mode {
       Mode::List => self.handle_list_event(event),
       Mode::Search => self.handle_search_event(event),
+      Mode::CargoOptions => self.handle_cargo_options_event(event),
+      Mode::Params => self.handle_params_event(event),
+      Mode::Output => self.handle_output_event(event),
     }
   }
 
@@ -110,24 +546,58 @@ impl State {
     }
   }
 
+  /// The keys [State::handle_list_keypress] handles before the selected runnable's own:
+  /// the list keys, and `o` which rust runnables handle before their custom actions.
+  /// Custom cargo actions on these keys are dropped.
+  pub const RESERVED_KEYS: &'static [char] = &['s', 'S', 'E', 'O', 'j', 'k', 'q', 'o'];
+
+  /// The keys of the built-in cargo actions of rust runnables,
+  /// which a custom action on the same key replaces.
+  pub const CARGO_KEYS: &'static [char] = &['r', 'R', 'p', 'i', 't', 'f', 'c', 'C', 'b', 'B'];
+
   /// returns true if should break render loop
   fn handle_list_keypress(&mut self, key: char) -> bool {
-    if key == 's' {
-      self.mode = Mode::Search;
-      return false;
-    } else if key == 'j' {
-      self.select_next();
-      return false;
-    } else if key == 'k' {
-      self.select_prev();
-      return false;
-    } else if key == 'q' {
-      // just quit
-      return true;
+    if Self::RESERVED_KEYS.contains(&key) {
+      match key {
+        's' => {
+          self.mode = Mode::Search;
+          return false;
+        }
+        'S' => {
+          self.stay = !self.stay;
+          return false;
+        }
+        'E' => {
+          self.embed = !self.embed;
+          return false;
+        }
+        'O' if !self.output.tabs.is_empty() => {
+          self.set_mode(Mode::Output);
+          return false;
+        }
+        'j' => {
+          self.select_next();
+          return false;
+        }
+        'k' => {
+          self.select_prev();
+          return false;
+        }
+        // just quit
+        'q' => return true,
+        // 'o' is handled by the rust runnables below.
+        _ => {}
+      }
     }
     match self.active.get(self.selected) {
       Some(selected) => match &selected.params {
-        RunnableParams::RunFile(_) => match key {
+        RunnableParams::RunFile(params) => match key {
+          'r' if !params.params.is_empty() => {
+            self.params_form = Some(ParamsForm::new(params.params.clone(), &self.args.params));
+            self.set_runnable();
+            self.set_mode(Mode::Params);
+            false
+          }
           'r' => {
             self.set_runnable();
             true
@@ -141,22 +611,108 @@ impl State {
           }
           _ => false,
         },
-        // RunnableParams::Javascript(_) => {
-        //   let command = match key {
-        //     'y' | 'r' => Some(JavascriptCommand::Yarn),
-        //     'n' => Some(JavascriptCommand::Npm),
-        //     _ => None,
-        //   };
-        //   if let Some(command) = command {
-        //     self.set_runnable();
-        //     self.runnable.params = RunnableParams::Javascript(JavascriptParams { command });
-        //     true
-        //   } else {
-        //     false
-        //   }
-        // }
-        RunnableParams::RustBin(_) => {
+        RunnableParams::Just(params) => match key {
+          'r' => {
+            let positional = params.positional();
+            self.set_runnable();
+            if positional.is_empty() {
+              return true;
+            }
+            self.params_form = Some(ParamsForm::new(positional, &self.args.params));
+            self.set_mode(Mode::Params);
+            false
+          }
+          _ => false,
+        },
+        RunnableParams::Make(_) | RunnableParams::Taskfile(_) | RunnableParams::CargoAlias(_) => {
+          match key {
+            'r' => {
+              self.set_runnable();
+              true
+            }
+            _ => false,
+          }
+        }
+        RunnableParams::Javascript(params) => {
+          let command = match key {
+            'r' => Some(params.command),
+            'y' => Some(JavascriptCommand::Yarn),
+            'n' => Some(JavascriptCommand::Npm),
+            'p' => Some(JavascriptCommand::Pnpm),
+            'b' => Some(JavascriptCommand::Bun),
+            _ => None,
+          };
+          if let Some(command) = command {
+            self.set_runnable();
+            self.runnable.params = RunnableParams::Javascript(JavascriptParams { command });
+            true
+          } else {
+            false
+          }
+        }
+        RunnableParams::Python(params) => {
+          let command = match (&params.target, key) {
+            (PythonTarget::Project, 't') => Some(PythonCommand::Test),
+            (PythonTarget::Project, 'l') => Some(PythonCommand::Lint),
+            (PythonTarget::Project, 'f') => Some(PythonCommand::Format),
+            (PythonTarget::Project, 'm') => Some(PythonCommand::TypeCheck),
+            (_, 'r') => Some(PythonCommand::Run),
+            _ => None,
+          };
+          if let Some(command) = command {
+            let params = PythonParams {
+              command,
+              ..params.clone()
+            };
+            self.set_runnable();
+            self.runnable.params = RunnableParams::Python(params);
+            true
+          } else {
+            false
+          }
+        }
+        RunnableParams::Go(params) => {
           let command = match key {
+            'r' => Some(GoCommand::Run),
+            'b' => Some(GoCommand::Build),
+            't' => Some(GoCommand::Test),
+            'T' => Some(GoCommand::TestRace),
+            'v' => Some(GoCommand::Vet),
+            'i' => Some(GoCommand::Install),
+            _ => None,
+          };
+          if let Some(command) = command {
+            let params = GoParams {
+              command,
+              ..params.clone()
+            };
+            self.set_runnable();
+            self.runnable.params = RunnableParams::Go(params);
+            true
+          } else {
+            false
+          }
+        }
+        RunnableParams::RustBin(params) => {
+          if key == 'o' {
+            self.pick_cargo_options = !self.pick_cargo_options;
+            return false;
+          }
+          // Custom actions run as declared, so they skip the feature / profile picker.
+          if let Some(action) = params.actions.iter().find(|action| action.key == key) {
+            let params = RustBinParams {
+              action: Some(action.clone()),
+              ..params.clone()
+            };
+            self.set_runnable();
+            self.runnable.params = RunnableParams::RustBin(params);
+            return true;
+          }
+          let command = match key {
+            // Benches and tests can't be run in release mode or installed.
+            'R' | 'i' if matches!(params.target, RustBinTarget::Bench | RustBinTarget::Test) => {
+              None
+            }
             'r' => Some(RustBinCommand::Run),
             'R' => Some(RustBinCommand::RunRelease),
             'p' => Some(RustBinCommand::Publish),
@@ -170,14 +726,30 @@ impl State {
             _ => None,
           };
           if let Some(command) = command {
-            self.set_runnable();
-            self.runnable.params = RunnableParams::RustBin(RustBinParams { command });
-            true
+            let params = RustBinParams {
+              command,
+              ..params.clone()
+            };
+            self.set_cargo_runnable(RunnableParams::RustBin(params))
           } else {
             false
           }
         }
-        RunnableParams::RustLib(_) => {
+        RunnableParams::RustLib(params) => {
+          if key == 'o' {
+            self.pick_cargo_options = !self.pick_cargo_options;
+            return false;
+          }
+          // Custom actions run as declared, so they skip the feature / profile picker.
+          if let Some(action) = params.actions.iter().find(|action| action.key == key) {
+            let params = RustLibParams {
+              action: Some(action.clone()),
+              ..params.clone()
+            };
+            self.set_runnable();
+            self.runnable.params = RunnableParams::RustLib(params);
+            return true;
+          }
           let command = match key {
             'p' => Some(RustLibCommand::Publish),
             't' => Some(RustLibCommand::Test),
@@ -188,9 +760,29 @@ impl State {
             'B' => Some(RustLibCommand::BuildRelease),
             _ => None,
           };
+          if let Some(command) = command {
+            let params = RustLibParams {
+              command,
+              ..params.clone()
+            };
+            self.set_cargo_runnable(RunnableParams::RustLib(params))
+          } else {
+            false
+          }
+        }
+        RunnableParams::RustWorkspace(_) => {
+          let command = match key {
+            't' => Some(RustWorkspaceCommand::Test),
+            'f' => Some(RustWorkspaceCommand::Fmt),
+            'c' => Some(RustWorkspaceCommand::Check),
+            'C' => Some(RustWorkspaceCommand::Clippy),
+            'b' => Some(RustWorkspaceCommand::Build),
+            'B' => Some(RustWorkspaceCommand::BuildRelease),
+            _ => None,
+          };
           if let Some(command) = command {
             self.set_runnable();
-            self.runnable.params = RunnableParams::RustLib(RustLibParams { command });
+            self.runnable.params = RunnableParams::RustWorkspace(RustWorkspaceParams { command });
             true
           } else {
             false
@@ -225,6 +817,36 @@ impl State {
     self.runnable = self.active[self.selected].as_ref().clone();
   }
 
+  /// Sets the rust runnable to run, first opening the feature / profile picker if enabled.
+  /// returns true if should break render loop
+  fn set_cargo_runnable(&mut self, params: RunnableParams) -> bool {
+    self.set_runnable();
+    self.runnable.params = params;
+    let takes_options = match &self.runnable.params {
+      RunnableParams::RustBin(params) => params.takes_options(),
+      RunnableParams::RustLib(params) => params.takes_options(),
+      _ => false,
+    };
+    if !self.pick_cargo_options || !takes_options {
+      return true;
+    }
+    let manifest_dir = match &self.runnable.params {
+      RunnableParams::RustBin(RustBinParams {
+        package: Some(package),
+        ..
+      })
+      | RunnableParams::RustLib(RustLibParams {
+        package: Some(package),
+        ..
+      }) => package.dir(&self.runnable.path),
+      _ => self.runnable.path.clone(),
+    };
+    let available = CargoOptionsAvailable::read(&manifest_dir);
+    self.cargo_options = Some(CargoOptionsPicker::new(available));
+    self.set_mode(Mode::CargoOptions);
+    false
+  }
+
   // ===================
   // SEARCH MODE
   // ===================
@@ -259,4 +881,243 @@ impl State {
     }
     false
   }
+
+  // ===================
+  // CARGO OPTIONS MODE
+  // ===================
+
+  /// returns true if should break render loop
+  fn handle_cargo_options_event(&mut self, event: Event) -> bool {
+    let Event::Key(key) = event else {
+      return false;
+    };
+    let Some(picker) = self.cargo_options.as_mut() else {
+      self.set_mode(Mode::List);
+      return false;
+    };
+    match key.code {
+      KeyCode::Char('j') | KeyCode::Down => picker.select_next(),
+      KeyCode::Char('k') | KeyCode::Up => picker.select_prev(),
+      KeyCode::Char(' ') => picker.toggle(),
+      KeyCode::Enter => {
+        let options = picker.options();
+        match &mut self.runnable.params {
+          RunnableParams::RustBin(params) => params.options = options,
+          RunnableParams::RustLib(params) => params.options = options,
+          _ => {}
+        }
+        self.cargo_options = None;
+        self.set_mode(Mode::List);
+        return true;
+      }
+      KeyCode::Esc | KeyCode::Char('q') => {
+        // Cancel, back to the list without running anything.
+        self.cargo_options = None;
+        self.runnable = Default::default();
+        self.set_mode(Mode::List);
+      }
+      _ => {}
+    }
+    false
+  }
+
+  // ===================
+  // PARAMS MODE
+  // ===================
+
+  /// returns true if should break render loop
+  fn handle_params_event(&mut self, event: Event) -> bool {
+    let Event::Key(key) = event else {
+      return false;
+    };
+    let Some(form) = self.params_form.as_mut() else {
+      self.set_mode(Mode::List);
+      return false;
+    };
+    match key.code {
+      KeyCode::Tab | KeyCode::Down => form.select_next(),
+      KeyCode::BackTab | KeyCode::Up => form.select_prev(),
+      KeyCode::Left if form.cycle(false) => {}
+      KeyCode::Right if form.cycle(true) => {}
+      KeyCode::Enter => match form.values() {
+        Ok(values) => {
+          match &mut self.runnable.params {
+            RunnableParams::RunFile(params) => params.values = values,
+            RunnableParams::Just(params) => params.values = values,
+            _ => {}
+          }
+          self.params_form = None;
+          self.set_mode(Mode::List);
+          return true;
+        }
+        Err(e) => form.error = Some(e.to_string()),
+      },
+      KeyCode::Esc => {
+        // Cancel, back to the list without running anything.
+        self.params_form = None;
+        self.runnable = Default::default();
+        self.set_mode(Mode::List);
+      }
+      _ => {
+        form.inputs[form.selected].handle_event(&Event::Key(key));
+      }
+    }
+    false
+  }
+
+  // ===================
+  // OUTPUT MODE
+  // ===================
+
+  fn handle_output_event(&mut self, event: Event) -> bool {
+    let Event::Key(key) = event else {
+      return false;
+    };
+    let output = &mut self.output;
+    if output.searching {
+      match key.code {
+        KeyCode::Enter => {
+          output.searching = false;
+          output.find(true, false);
+        }
+        KeyCode::Esc => {
+          output.search.reset();
+          output.searching = false;
+        }
+        _ => {
+          output.search.handle_event(&Event::Key(key));
+        }
+      }
+      return false;
+    }
+    let page = (output.height / 2).max(1) as isize;
+    match key.code {
+      KeyCode::Char('j') | KeyCode::Down => output.scroll(1),
+      KeyCode::Char('k') | KeyCode::Up => output.scroll(-1),
+      KeyCode::Char('d') | KeyCode::PageDown => output.scroll(page),
+      KeyCode::Char('u') | KeyCode::PageUp => output.scroll(-page),
+      KeyCode::Char('g') | KeyCode::Home => output.scroll_to(Some(0)),
+      KeyCode::Char('G') | KeyCode::End => output.scroll_to(None),
+      KeyCode::Char('h') | KeyCode::Left => output.select_prev(),
+      KeyCode::Char('l') | KeyCode::Right => output.select_next(),
+      KeyCode::Char('/') => output.searching = true,
+      KeyCode::Char('n') => output.find(true, true),
+      KeyCode::Char('N') => output.find(false, true),
+      KeyCode::Char('x') => {
+        if let Some(tab) = output.tab() {
+          tab.job.kill();
+        }
+      }
+      KeyCode::Char('r') => output.restart(),
+      KeyCode::Char('c') => {
+        output.close();
+        if output.tabs.is_empty() {
+          self.set_mode(Mode::List);
+        }
+      }
+      KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('O') => self.set_mode(Mode::List),
+      _ => {}
+    }
+    false
+  }
+}
+
+#[cfg(test)]
+mod state_tests {
+  use anyhow::anyhow;
+
+  use super::{OutputPane, ParamsForm};
+  use crate::{
+    jobs::{Job, JobPlan, MAX_LINES},
+    sources::runfile::RunFileParam,
+  };
+
+  /// A pane with a job which never started a process, holding `lines` lines.
+  fn pane(lines: usize) -> OutputPane {
+    let job = Job::failed(
+      JobPlan::new(Default::default(), &[], 1, &[]),
+      &anyhow!("line 0"),
+    );
+    for i in 1..lines {
+      job.push_line(format!("\x1b[31mline {i}\x1b[0m"));
+    }
+    let mut pane = OutputPane {
+      height: 10,
+      ..Default::default()
+    };
+    pane.open(job);
+    pane
+  }
+
+  fn top(pane: &OutputPane) -> usize {
+    pane.top(pane.tab().unwrap())
+  }
+
+  #[test]
+  fn output_pane_scrolls_and_searches() {
+    let mut pane = pane(100);
+    // Follows the end of the output.
+    assert_eq!(top(&pane), 90);
+    pane.scroll(-5);
+    assert_eq!(top(&pane), 85);
+    pane.scroll(-1000);
+    assert_eq!(top(&pane), 0);
+    // Back at the end, it follows the output again.
+    pane.scroll(1000);
+    assert_eq!(pane.tab().unwrap().scroll, None);
+
+    pane.search = "LINE 4".into();
+    pane.find(true, true);
+    assert_eq!(top(&pane), 4);
+    pane.find(true, true);
+    assert_eq!(top(&pane), 40);
+    pane.find(false, true);
+    assert_eq!(top(&pane), 4);
+    // Wraps around.
+    pane.find(false, true);
+    assert_eq!(top(&pane), 49);
+  }
+
+  #[test]
+  fn output_pane_keeps_the_latest_lines() {
+    let mut pane = pane(MAX_LINES + 50);
+    let job = pane.tab().unwrap().job.clone();
+    assert_eq!(job.lines().first(), 50);
+    assert_eq!(top(&pane), MAX_LINES + 40);
+    pane.scroll(-(MAX_LINES as isize));
+    assert_eq!(top(&pane), 50);
+    // The view stays on the same lines as older ones are dropped, until they are gone.
+    pane.scroll(100);
+    job.push_line(String::from("more"));
+    assert_eq!(top(&pane), 150);
+    assert_eq!(job.lines().get(150).unwrap(), "\x1b[31mline 150\x1b[0m");
+    pane.search = "line 0".into();
+    pane.find(true, false);
+    assert_eq!(top(&pane), 150);
+  }
+
+  #[test]
+  fn params_form_requires_params_without_default() {
+    let params: Vec<RunFileParam> = toml::from_str::<toml::Table>(
+      r#"
+params = [
+  { name = "env" },
+  { name = "tag", default = "" },
+]
+"#,
+    )
+    .unwrap()["params"]
+      .clone()
+      .try_into()
+      .unwrap();
+    let mut form = ParamsForm::new(params, &[]);
+    assert_eq!(
+      form.values().unwrap_err().to_string(),
+      "env: a value is required"
+    );
+    form.inputs[0] = "dev".into();
+    let values = form.values().unwrap();
+    assert_eq!(values["env"], "dev");
+    assert_eq!(values["tag"], "");
+  }
 }
@@ -14,11 +14,14 @@ use ratatui::{
   Terminal,
 };
 
-use crate::state::State;
+use crate::{interrupt, jobs::JobStatus, state::State, types::RunnableParams};
 
 mod ui;
 
 pub fn run(state: &mut State) -> anyhow::Result<()> {
+  // So the jobs in the output pane can be stopped before `run` exits.
+  interrupt::forward_signals()?;
+  interrupt::TERMINAL.reset();
   enable_raw_mode()?;
   let mut stdout = io::stdout();
   execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
@@ -48,10 +51,23 @@ fn render_loop(
 ) -> anyhow::Result<()> {
   let root_path = state.root_absolute_path()?;
   loop {
+    if interrupt::TERMINAL.interrupted() {
+      state.runnable = Default::default();
+      break;
+    }
     terminal.draw(|frame| ui::render(frame, state, &root_path).expect("failed to draw frame"))?;
-    if let Some(event) = poll_event()? {
+    // Redraw more often while there is output coming in.
+    let running = state
+      .output
+      .tabs
+      .iter()
+      .any(|tab| tab.job.status() == JobStatus::Running);
+    let timeout = Duration::from_millis(if running { 100 } else { 250 });
+    if let Some(event) = poll_event(timeout)? {
       let should_break = state.handle_event(event);
-      if should_break {
+      if should_break && state.embed && !matches!(state.runnable.params, RunnableParams::None) {
+        state.start_job();
+      } else if should_break {
         break;
       }
     }
@@ -59,8 +75,8 @@ fn render_loop(
   Ok(())
 }
 
-fn poll_event() -> anyhow::Result<Option<Event>> {
-  if event::poll(Duration::from_millis(250)).context("event poll failed")? {
+fn poll_event(timeout: Duration) -> anyhow::Result<Option<Event>> {
+  if event::poll(timeout).context("event poll failed")? {
     let event = event::read().context("event read failed")?;
     Ok(Some(event))
   } else {
@@ -1,22 +1,36 @@
+use ansi_to_tui::IntoText;
 use ratatui::{
   prelude::{Constraint, Direction, Layout, Margin, Rect},
   style::{Color, Style, Stylize},
   text::{Line, Span},
-  widgets::{Block, Borders, Paragraph, Row, Table, Wrap},
+  widgets::{Block, Borders, Clear, Paragraph, Row, Table, Wrap},
   Frame,
 };
 
 use crate::{
+  dotenv,
   helpers::runnable_path_display,
-  sources::runfile::RunFileParams,
-  state::{Mode, State},
+  jobs::JobStatus,
+  runnables::RunOutcome,
+  sources::{
+    cargo_alias::CargoAliasParams,
+    go::GoParams,
+    just::JustParams,
+    make::MakeParams,
+    python::{PythonParams, PythonTarget},
+    runfile::RunFileParams,
+    rust_bin::{RustBinParams, RustBinTarget},
+    rust_lib::RustLibParams,
+    shell::ShellParams,
+  },
+  state::{LastRun, Mode, State},
   types::RunnableParams,
 };
 
 pub fn render(frame: &mut Frame, state: &mut State, root_path: &str) -> anyhow::Result<()> {
   let frame_size = frame.area().inner(Margin::new(1, 1));
 
-  render_bounder(frame, root_path, frame_size);
+  render_bounder(frame, state, root_path, frame_size);
 
   let v_layout = Layout::default()
     .direction(Direction::Vertical)
@@ -26,19 +40,38 @@ pub fn render(frame: &mut Frame, state: &mut State, root_path: &str) -> anyhow::
 
   render_search(frame, state, v_layout[0]);
 
+  let main_area = if state.output.tabs.is_empty() {
+    v_layout[1]
+  } else {
+    let output_layout = Layout::default()
+      .direction(Direction::Vertical)
+      .constraints(vec![Constraint::Percentage(45), Constraint::Percentage(55)])
+      .split(v_layout[1]);
+    render_output(frame, state, output_layout[1]);
+    output_layout[0]
+  };
+
   let h_layout = Layout::default()
     .direction(Direction::Horizontal)
     .constraints(vec![Constraint::Min(64), Constraint::Percentage(100)])
-    .split(v_layout[1]);
+    .split(main_area);
 
   render_list(frame, state, &h_layout);
   render_info(frame, state, root_path, &h_layout)?;
 
+  if state.mode == Mode::CargoOptions {
+    render_cargo_options(frame, state, frame_size);
+  }
+
+  if state.mode == Mode::Params {
+    render_params_form(frame, state, frame_size);
+  }
+
   Ok(())
 }
 
-fn render_bounder(frame: &mut Frame, root_path: &str, frame_size: Rect) {
-  let border = Block::default()
+fn render_bounder(frame: &mut Frame, state: &State, root_path: &str, frame_size: Rect) {
+  let mut border = Block::default()
     .title(Span::styled(
       "runnables-cli",
       Style::default().light_blue().bold(),
@@ -47,10 +80,35 @@ fn render_bounder(frame: &mut Frame, root_path: &str, frame_size: Rect) {
     .title_bottom(
       Span::styled("press 'q' to quit", Style::default().bold()).into_right_aligned_line(),
     );
+  if let Some(last_run) = &state.last_run {
+    border = border.title_bottom(last_run_status(last_run));
+  }
 
   frame.render_widget(border, frame_size);
 }
 
+/// The exit status and duration of the previous run, for the status bar.
+fn last_run_status(last_run: &LastRun) -> Line<'static> {
+  let elapsed = format!("{:.2?}", last_run.elapsed);
+  let status = match &last_run.outcome {
+    Ok(RunOutcome {
+      interrupted: true, ..
+    }) => Span::from(format!("interrupted after {elapsed}")).yellow(),
+    Ok(outcome) if outcome.status.success() => Span::from(format!("finished in {elapsed}")).green(),
+    Ok(outcome) => Span::from(format!(
+      "failed (code {}) in {elapsed}",
+      outcome.status.code().unwrap_or(1)
+    ))
+    .red(),
+    Err(e) => Span::from(format!("failed to start: {e}")).red(),
+  };
+  Line::from(vec![
+    Span::from(format!(" {}: ", last_run.name)).bold(),
+    status.bold(),
+    Span::from(" "),
+  ])
+}
+
 fn render_search(frame: &mut Frame, state: &State, frame_size: Rect) {
   let value = state.search.value();
   let value = if state.mode == Mode::List && value.is_empty() {
@@ -61,7 +119,7 @@ fn render_search(frame: &mut Frame, state: &State, frame_size: Rect) {
   let search = Paragraph::new(value)
     .style(match state.mode {
       Mode::Search => Style::default().fg(state.args.color),
-      Mode::List => Style::default(),
+      Mode::List | Mode::CargoOptions | Mode::Params | Mode::Output => Style::default(),
     })
     .block(Block::default().title("search").borders(Borders::ALL));
   frame.render_widget(search, frame_size);
@@ -75,18 +133,48 @@ fn render_search(frame: &mut Frame, state: &State, frame_size: Rect) {
   }
 }
 
+/// The name with the characters matched by the search highlighted.
+fn highlight_name<'a>(name: &str, positions: &[usize]) -> Line<'a> {
+  let mut spans = Vec::<Span>::new();
+  let mut chunk = String::new();
+  let mut matched = false;
+  for (i, c) in name.chars().enumerate() {
+    let is_match = positions.contains(&i);
+    if is_match != matched && !chunk.is_empty() {
+      spans.push(name_span(std::mem::take(&mut chunk), matched));
+    }
+    matched = is_match;
+    chunk.push(c);
+  }
+  spans.push(name_span(chunk, matched));
+  Line::from(spans)
+}
+
+fn name_span<'a>(text: String, matched: bool) -> Span<'a> {
+  if matched {
+    text.yellow().bold()
+  } else {
+    text.light_blue()
+  }
+}
+
 fn render_list(frame: &mut Frame, state: &mut State, layout: &[Rect]) {
   state.set_active_runnables();
 
   let table_items = state.active.iter().enumerate().map(|(i, runnable)| {
     let row = vec![
-      runnable
-        .display_name
-        .clone()
-        .unwrap_or(runnable.name.clone())
-        .light_blue(),
-      Span::from(runnable.params.to_string()),
-      Span::from(runnable.aliases.join(", ")),
+      highlight_name(
+        runnable.display_name.as_deref().unwrap_or(&runnable.name),
+        &state.highlights[i],
+      ),
+      match state.output.status(runnable.index) {
+        Some(status) => Line::from(vec![
+          Span::from(format!("{} ", runnable.params)),
+          job_status_symbol(status),
+        ]),
+        None => Line::from(runnable.params.to_string()),
+      },
+      Line::from(runnable.aliases.join(", ")),
     ];
     if i == state.selected {
       Row::new(row).bold().underlined()
@@ -105,11 +193,150 @@ fn render_list(frame: &mut Frame, state: &mut State, layout: &[Rect]) {
   )
   .header(Row::new(["Name", "Type", "Alias"]).dim().underlined())
   .block(Block::default().borders(Borders::ALL).fg(match state.mode {
-    Mode::List => state.args.color,
-    Mode::Search => Color::White,
+    Mode::List | Mode::CargoOptions | Mode::Params => state.args.color,
+    Mode::Search | Mode::Output => Color::White,
   }));
 
-  frame.render_widget(table, layout[0]);
+  // Only used to scroll the selection into view, it is styled above.
+  state.table.select(Some(state.selected));
+  frame.render_stateful_widget(table, layout[0], &mut state.table);
+}
+
+/// Marks how the run of a runnable in the output pane is doing.
+fn job_status_symbol(status: JobStatus) -> Span<'static> {
+  match status {
+    JobStatus::Running => Span::from("●").yellow(),
+    JobStatus::Succeeded => Span::from("✓").green(),
+    JobStatus::Failed(_) => Span::from("✗").red(),
+    JobStatus::Killed => Span::from("■").dark_gray(),
+  }
+}
+
+fn render_output(frame: &mut Frame, state: &mut State, area: Rect) {
+  let focused = state.mode == Mode::Output;
+  let color = state.args.color;
+  let output = &mut state.output;
+  output.height = area.height.saturating_sub(2) as usize;
+  let Some(tab) = output.tab() else {
+    return;
+  };
+
+  let mut tabs = vec![Span::from(" ")];
+  for (i, tab) in output.tabs.iter().enumerate() {
+    let name = Span::from(format!(" {} ", tab.job.plan.runnable.name));
+    tabs.push(if i == output.selected {
+      name.bold().reversed()
+    } else {
+      name
+    });
+    tabs.push(job_status_symbol(tab.job.status()));
+    tabs.push(Span::from(" "));
+  }
+
+  let elapsed = format!("{:.2?}", tab.job.elapsed());
+  let status = match tab.job.status() {
+    JobStatus::Running => Span::from(format!("running {elapsed}")).yellow(),
+    JobStatus::Succeeded => Span::from(format!("finished in {elapsed}")).green(),
+    JobStatus::Failed(code) => Span::from(format!("failed (code {code}) in {elapsed}")).red(),
+    JobStatus::Killed => Span::from(format!("killed after {elapsed}")).dark_gray(),
+  };
+  let bottom = if output.searching {
+    Line::from(format!("/{}", output.search.value()))
+  } else if focused {
+    Line::from(
+      " j/k: scroll, g/G: top/follow, h/l: tabs, /: search, n/N: next/prev, x: kill, r: restart, c: close, O: back ",
+    )
+    .dim()
+  } else {
+    Line::from(" O: focus output ").dim()
+  };
+
+  let query = output.search.value().to_ascii_lowercase();
+  let top = output.top(tab);
+  let lines = tab
+    .job
+    .lines()
+    .from(top)
+    .take(output.height)
+    .map(|line| {
+      // Only the last redraw of progress bars which use carriage returns.
+      let line = line.rsplit('\r').next().unwrap_or_default();
+      let line = line
+        .replace('\t', "    ")
+        .into_text()
+        .ok()
+        .and_then(|text| text.lines.into_iter().next())
+        .unwrap_or_default();
+      highlight(line, &query)
+    })
+    .collect::<Vec<_>>();
+
+  let block = Block::default()
+    .borders(Borders::ALL)
+    .border_style(Style::default().fg(if focused { color } else { Color::White }))
+    .title(Line::from(tabs))
+    .title(Line::from(vec![Span::from(" "), status.bold(), Span::from(" ")]).right_aligned())
+    .title_bottom(bottom);
+  frame.render_widget(Paragraph::new(lines).block(block), area);
+
+  if output.searching {
+    frame.set_cursor_position((
+      // Past the `/` on the bottom border
+      area.x + 2 + output.search.visual_cursor() as u16,
+      area.y + area.height.saturating_sub(1),
+    ));
+  }
+}
+
+/// Highlights where the (lowercase) query appears in the line, ignoring ASCII case.
+fn highlight(line: Line<'static>, query: &str) -> Line<'static> {
+  if query.is_empty() {
+    return line;
+  }
+  let text = line
+    .spans
+    .iter()
+    .map(|span| span.content.as_ref())
+    .collect::<String>()
+    .to_ascii_lowercase();
+  let matches = text
+    .match_indices(query)
+    .map(|(start, _)| start..start + query.len())
+    .collect::<Vec<_>>();
+  if matches.is_empty() {
+    return line;
+  }
+  let highlighted = Style::default().black().on_yellow();
+  let mut spans = Vec::new();
+  let mut offset = 0;
+  for span in line.spans {
+    let content = span.content.as_ref();
+    let end = offset + content.len();
+    // Split the span where a match starts or ends inside it.
+    let mut cuts = vec![offset, end];
+    for range in &matches {
+      cuts.extend(
+        [range.start, range.end]
+          .into_iter()
+          .filter(|&cut| offset < cut && cut < end),
+      );
+    }
+    cuts.sort_unstable();
+    cuts.dedup();
+    for cut in cuts.windows(2) {
+      let (from, to) = (cut[0], cut[1]);
+      let mut piece = Span::styled(content[from - offset..to - offset].to_string(), span.style);
+      if matches
+        .iter()
+        .any(|range| range.start <= from && to <= range.end)
+      {
+        piece = piece.patch_style(highlighted);
+      }
+      spans.push(piece);
+    }
+    offset = end;
+  }
+  Line::from(spans).style(line.style)
 }
 
 fn render_info(
@@ -150,6 +377,48 @@ fn render_info(
           .bold(),
       ]));
 
+      let shell = match &selected.params {
+        RunnableParams::RunFile(RunFileParams { shell, .. }) => shell.as_ref(),
+        RunnableParams::Shell(ShellParams { shebang }) => shebang.as_ref(),
+        _ => None,
+      };
+      if let Some(shell) = shell {
+        lines.push(Line::from(vec![
+          Span::from("shell: "),
+          Span::from(shell).light_blue().bold(),
+        ]));
+      }
+
+      if let RunnableParams::Make(MakeParams { phony: true }) = &selected.params {
+        lines.push(Line::from(vec![
+          Span::from("phony: "),
+          Span::from("true").light_blue().bold(),
+        ]));
+      }
+
+      if let RunnableParams::CargoAlias(CargoAliasParams { expansion, .. }) = &selected.params {
+        lines.push(Line::from(vec![
+          Span::from("expands to: "),
+          Span::from(format!("cargo {expansion}")).light_blue().bold(),
+        ]));
+      }
+
+      if let RunnableParams::Go(GoParams { module, .. }) = &selected.params {
+        lines.push(Line::from(vec![
+          Span::from("module: "),
+          Span::from(module).light_blue().bold(),
+        ]));
+      }
+
+      if let RunnableParams::Just(JustParams { parameters, .. }) = &selected.params {
+        if !parameters.is_empty() {
+          lines.push(Line::from(vec![
+            Span::from("parameters: "),
+            Span::from(parameters.join(" ")).light_blue().bold(),
+          ]));
+        }
+      }
+
       if let Some(after) = &selected.after {
         lines.push(Line::from(vec![
           Span::from("after: "),
@@ -165,16 +434,89 @@ fn render_info(
       lines.push(Line::from(""));
       lines.push(Line::from(description));
 
-      if let RunnableParams::RunFile(RunFileParams { command }) = &selected.params {
+      if let RunnableParams::RunFile(RunFileParams {
+        command,
+        params,
+        env,
+        missing_env_files,
+        ..
+      }) = &selected.params
+      {
         lines.push(Line::from(""));
         lines.push(Line::from(Span::from(command).light_blue().bold()));
+        if !params.is_empty() {
+          lines.push(Line::from(""));
+          lines.push(Line::from("params:"));
+        }
+        for param in params {
+          let mut line = vec![
+            Span::from(format!("  {}", param.name)).light_blue().bold(),
+            Span::from(format!(" ({})", param.kind)).dim(),
+          ];
+          match param.default_value() {
+            Some(default) => line.push(Span::from(format!(" = {default}"))),
+            None => line.push(Span::from(" required").dim()),
+          }
+          if let Some(description) = &param.description {
+            line.push(Span::from(format!("  {description}")));
+          }
+          lines.push(Line::from(line));
+        }
+        if !env.is_empty() || !missing_env_files.is_empty() {
+          lines.push(Line::from(""));
+          lines.push(Line::from("env:"));
+        }
+        for (key, value) in env {
+          let value = if dotenv::is_secret(key) {
+            Span::from("********").dim()
+          } else {
+            Span::from(value.as_str())
+          };
+          lines.push(Line::from(vec![
+            Span::from(format!("  {key}")).light_blue().bold(),
+            Span::from("="),
+            value,
+          ]));
+        }
+        for env_file in missing_env_files {
+          lines.push(Line::from(format!("  env_file not found: {}", env_file.display())).red());
+        }
       }
 
       lines.push(Line::from(""));
       lines.extend(keypress_helper(&selected.params));
+      if let RunnableParams::RustBin(_) | RunnableParams::RustLib(_) = &selected.params {
+        lines.push(Line::from(vec![
+          Span::from("o").bold().light_blue(),
+          Span::from(": pick features / profile before running: "),
+          if state.pick_cargo_options {
+            Span::from("on").light_blue().bold()
+          } else {
+            Span::from("off").dim()
+          },
+        ]));
+      }
     }
     None => lines.push(Line::from("-- NO RUNNABLE SELECTED --")),
   }
+  lines.push(Line::from(vec![
+    Span::from("E").bold().light_blue(),
+    Span::from(": run in the output pane: "),
+    if state.embed {
+      Span::from("on").light_blue().bold()
+    } else {
+      Span::from("off").dim()
+    },
+  ]));
+  lines.push(Line::from(vec![
+    Span::from("S").bold().light_blue(),
+    Span::from(": come back here after running: "),
+    if state.stay {
+      Span::from("on").light_blue().bold()
+    } else {
+      Span::from("off").dim()
+    },
+  ]));
   let info = Paragraph::new(lines)
     .block(Block::default().borders(Borders::ALL))
     .wrap(Wrap { trim: true });
@@ -184,7 +526,172 @@ fn render_info(
   Ok(())
 }
 
+fn render_cargo_options(frame: &mut Frame, state: &State, frame_size: Rect) {
+  let Some(picker) = &state.cargo_options else {
+    return;
+  };
+
+  let item = |index: usize, checked: bool, radio: bool, label: &str| {
+    let mark = match (radio, checked) {
+      (false, true) => "[x] ",
+      (false, false) => "[ ] ",
+      (true, true) => "(*) ",
+      (true, false) => "( ) ",
+    };
+    let line = Line::from(format!("{mark}{label}"));
+    if index == picker.selected {
+      line.fg(state.args.color).bold().underlined()
+    } else {
+      line
+    }
+  };
+
+  let mut lines: Vec<Line> = vec![Line::from("features").dim().underlined()];
+  if picker.features.is_empty() {
+    lines.push(Line::from("-- NO FEATURES --").dim());
+  }
+  for (index, (feature, enabled)) in picker
+    .available
+    .features
+    .iter()
+    .zip(&picker.features)
+    .enumerate()
+  {
+    lines.push(item(index, *enabled, false, feature));
+  }
+  let index = picker.features.len();
+  lines.push(item(
+    index,
+    picker.no_default_features,
+    false,
+    "no default features",
+  ));
+  lines.push(Line::from(""));
+  lines.push(Line::from("profile").dim().underlined());
+  for (profile, name) in picker.available.profiles.iter().enumerate() {
+    lines.push(item(
+      index + 1 + profile,
+      picker.profile == Some(profile),
+      true,
+      name,
+    ));
+  }
+  lines.push(Line::from(""));
+  lines.push(Line::from(vec![
+    Span::from("space").bold().light_blue(),
+    Span::from(": toggle  "),
+    Span::from("enter").bold().light_blue(),
+    Span::from(": run  "),
+    Span::from("esc").bold().light_blue(),
+    Span::from(": cancel"),
+  ]));
+
+  let width = frame_size.width.min(60);
+  let height = (lines.len() as u16 + 2).min(frame_size.height);
+  let area = Rect::new(
+    frame_size.x + (frame_size.width - width) / 2,
+    frame_size.y + (frame_size.height - height) / 2,
+    width,
+    height,
+  );
+  let popup = Paragraph::new(lines).block(
+    Block::default()
+      .title("cargo options")
+      .borders(Borders::ALL)
+      .fg(state.args.color),
+  );
+  frame.render_widget(Clear, area);
+  frame.render_widget(popup, area);
+}
+
+fn render_params_form(frame: &mut Frame, state: &State, frame_size: Rect) {
+  let Some(form) = &state.params_form else {
+    return;
+  };
+
+  let mut lines: Vec<Line> = Vec::new();
+  // The column and line of the cursor in the selected input.
+  let mut cursor = (0, 0);
+  for (index, (param, input)) in form.params.iter().zip(&form.inputs).enumerate() {
+    let mut label = vec![
+      Span::from(param.name.as_str()).bold(),
+      Span::from(format!(" ({})", param.kind)).dim(),
+    ];
+    if let Some(description) = &param.description {
+      label.push(Span::from(format!("  {description}")));
+    }
+    lines.push(Line::from(label));
+    let value = Line::from(format!("> {}", input.value()));
+    if index == form.selected {
+      cursor = (input.visual_cursor() + 2, lines.len());
+      lines.push(value.fg(state.args.color).bold());
+    } else {
+      lines.push(value);
+    }
+    let choices = param.choices();
+    if !choices.is_empty() {
+      lines.push(Line::from(format!("  choices: {}", choices.join(", "))).dim());
+    }
+    lines.push(Line::from(""));
+  }
+  if let Some(error) = &form.error {
+    lines.push(Line::from(error.as_str()).red().bold());
+    lines.push(Line::from(""));
+  }
+  lines.push(Line::from(vec![
+    Span::from("tab").bold().light_blue(),
+    Span::from(": next  "),
+    Span::from("left / right").bold().light_blue(),
+    Span::from(": change choice  "),
+    Span::from("enter").bold().light_blue(),
+    Span::from(": run  "),
+    Span::from("esc").bold().light_blue(),
+    Span::from(": cancel"),
+  ]));
+
+  let width = frame_size.width.min(80);
+  let height = (lines.len() as u16 + 2).min(frame_size.height);
+  let area = Rect::new(
+    frame_size.x + (frame_size.width - width) / 2,
+    frame_size.y + (frame_size.height - height) / 2,
+    width,
+    height,
+  );
+  let popup = Paragraph::new(lines).block(
+    Block::default()
+      .title(format!("params: {}", state.runnable.name))
+      .borders(Borders::ALL)
+      .fg(state.args.color),
+  );
+  frame.render_widget(Clear, area);
+  frame.render_widget(popup, area);
+  frame.set_cursor_position((area.x + 1 + cursor.0 as u16, area.y + 1 + cursor.1 as u16));
+}
+
 fn keypress_helper(params: &RunnableParams) -> Vec<Line<'static>> {
+  let actions = match params {
+    RunnableParams::RustBin(RustBinParams { actions, .. })
+    | RunnableParams::RustLib(RustLibParams { actions, .. }) => actions.as_slice(),
+    _ => &[],
+  };
+  let mut lines = command_keypress_helper(params);
+  // Custom actions take over the keys they declare.
+  lines.retain(|line| {
+    let key = line.spans.first().map(|span| span.content.as_ref());
+    !actions
+      .iter()
+      .any(|action| key == Some(action.key.to_string().as_str()))
+  });
+  lines.extend(actions.iter().map(|action| {
+    Line::from(vec![
+      Span::from(action.key.to_string()).bold().light_blue(),
+      Span::from(format!(": {}", action.label)),
+    ])
+  }));
+  lines
+}
+
+fn command_keypress_helper(params: &RunnableParams) -> Vec<Line<'static>> {
   match params {
     RunnableParams::RunFile(_) => vec![
       // Line::from("actions:"),
@@ -202,18 +709,134 @@ fn keypress_helper(params: &RunnableParams) -> Vec<Line<'static>> {
         Span::from(": run"),
       ]),
     ],
-    // RunnableParams::Javascript(_) => vec![
-    //   // Line::from("actions:"),
-    //   // Line::from(""),
-    //   Line::from(vec![
-    //     Span::from("y").bold().light_blue(),
-    //     Span::from(": yarn"),
-    //   ]),
-    //   Line::from(vec![
-    //     Span::from("n").bold().light_blue(),
-    //     Span::from(": npm"),
-    //   ]),
-    // ],
+    RunnableParams::Make(_)
+    | RunnableParams::Just(_)
+    | RunnableParams::Taskfile(_)
+    | RunnableParams::CargoAlias(_) => {
+      vec![Line::from(vec![
+        Span::from("r").bold().light_blue(),
+        Span::from(": run"),
+      ])]
+    }
+    RunnableParams::Javascript(params) => vec![
+      Line::from(vec![
+        Span::from("r").bold().light_blue(),
+        Span::from(format!(": run ({})", params.command)),
+      ]),
+      Line::from(vec![
+        Span::from("y").bold().light_blue(),
+        Span::from(": yarn"),
+      ]),
+      Line::from(vec![
+        Span::from("n").bold().light_blue(),
+        Span::from(": npm"),
+      ]),
+      Line::from(vec![
+        Span::from("p").bold().light_blue(),
+        Span::from(": pnpm"),
+      ]),
+      Line::from(vec![
+        Span::from("b").bold().light_blue(),
+        Span::from(": bun"),
+      ]),
+    ],
+    RunnableParams::Python(PythonParams {
+      target: PythonTarget::Project,
+      runner,
+      ..
+    }) => vec![
+      Line::from(vec![
+        Span::from("r").bold().light_blue(),
+        Span::from(": run (the tests)"),
+      ]),
+      Line::from(vec![
+        Span::from("t").bold().light_blue(),
+        Span::from(format!(": test ({runner} pytest)")),
+      ]),
+      Line::from(vec![
+        Span::from("l").bold().light_blue(),
+        Span::from(format!(": lint ({runner} ruff check)")),
+      ]),
+      Line::from(vec![
+        Span::from("f").bold().light_blue(),
+        Span::from(format!(": format ({runner} ruff format)")),
+      ]),
+      Line::from(vec![
+        Span::from("m").bold().light_blue(),
+        Span::from(format!(": type check ({runner} mypy)")),
+      ]),
+    ],
+    RunnableParams::Python(_) => vec![Line::from(vec![
+      Span::from("r").bold().light_blue(),
+      Span::from(": run"),
+    ])],
+    RunnableParams::Go(_) => vec![
+      Line::from(vec![
+        Span::from("r").bold().light_blue(),
+        Span::from(": run"),
+      ]),
+      Line::from(vec![
+        Span::from("b").bold().light_blue(),
+        Span::from(": build"),
+      ]),
+      Line::from(vec![
+        Span::from("t").bold().light_blue(),
+        Span::from(": test"),
+      ]),
+      Line::from(vec![
+        Span::from("T").bold().light_blue(),
+        Span::from(": test (race)"),
+      ]),
+      Line::from(vec![
+        Span::from("v").bold().light_blue(),
+        Span::from(": vet"),
+      ]),
+      Line::from(vec![
+        Span::from("i").bold().light_blue(),
+        Span::from(": install"),
+      ]),
+    ],
+    RunnableParams::RustBin(RustBinParams {
+      target: target @ (RustBinTarget::Bench | RustBinTarget::Test),
+      ..
+    }) => vec![
+      Line::from(vec![
+        Span::from("r").bold().light_blue(),
+        Span::from(if *target == RustBinTarget::Bench {
+          ": bench"
+        } else {
+          ": run test"
+        }),
+      ]),
+      Line::from(vec![
+        Span::from("p").bold().light_blue(),
+        Span::from(": publish"),
+      ]),
+      Line::from(vec![
+        Span::from("b").bold().light_blue(),
+        Span::from(": build"),
+      ]),
+      Line::from(vec![
+        Span::from("B").bold().light_blue(),
+        Span::from(": build release"),
+      ]),
+      Line::from(vec![
+        Span::from("t").bold().light_blue(),
+        Span::from(": test"),
+      ]),
+      Line::from(vec![
+        Span::from("c").bold().light_blue(),
+        Span::from(": check"),
+      ]),
+      Line::from(vec![
+        Span::from("C").bold().light_blue(),
+        Span::from(": clippy"),
+      ]),
+      Line::from(vec![
+        Span::from("f").bold().light_blue(),
+        Span::from(": format"),
+      ]),
+    ],
     RunnableParams::RustBin(_) => vec![
       Line::from(vec![
         Span::from("r").bold().light_blue(),
@@ -286,6 +909,32 @@ fn keypress_helper(params: &RunnableParams) -> Vec<Line<'static>> {
         Span::from(": format"),
       ]),
     ],
+    RunnableParams::RustWorkspace(_) => vec![
+      Line::from(vec![
+        Span::from("b").bold().light_blue(),
+        Span::from(": build"),
+      ]),
+      Line::from(vec![
+        Span::from("B").bold().light_blue(),
+        Span::from(": build release"),
+      ]),
+      Line::from(vec![
+        Span::from("t").bold().light_blue(),
+        Span::from(": test"),
+      ]),
+      Line::from(vec![
+        Span::from("c").bold().light_blue(),
+        Span::from(": check"),
+      ]),
+      Line::from(vec![
+        Span::from("C").bold().light_blue(),
+        Span::from(": clippy"),
+      ]),
+      Line::from(vec![
+        Span::from("f").bold().light_blue(),
+        Span::from(": format"),
+      ]),
+    ],
     RunnableParams::None => {
       panic!("tried to get keypress helpers for None variant")
     }
@@ -5,8 +5,23 @@ use colored::Colorize;
 use derive_variants::EnumVariants;
 use strum::EnumString;
 
-use crate::sources::{
-  runfile::RunFileParams, rust_bin::RustBinParams, rust_lib::RustLibParams, shell::ShellParams,
+use crate::{
+  config::config,
+  helpers::shell_join,
+  sources::{
+    cargo_alias::CargoAliasParams,
+    go::GoParams,
+    javascript::JavascriptParams,
+    just::JustParams,
+    make::MakeParams,
+    python::PythonParams,
+    runfile::RunFileParams,
+    rust_bin::{RustBinParams, RustBinTarget},
+    rust_lib::RustLibParams,
+    rust_workspace::RustWorkspaceParams,
+    shell::ShellParams,
+    taskfile::TaskfileParams,
+  },
 };
 
 #[derive(Clone, Debug, Default)]
@@ -33,6 +48,14 @@ pub enum RunnableParams {
   Shell(ShellParams),
   RustBin(RustBinParams),
   RustLib(RustLibParams),
+  RustWorkspace(RustWorkspaceParams),
+  CargoAlias(CargoAliasParams),
+  Javascript(JavascriptParams),
+  Make(MakeParams),
+  Just(JustParams),
+  Taskfile(TaskfileParams),
+  Python(PythonParams),
+  Go(GoParams),
 }
 
 impl Display for RunnableParams {
@@ -40,8 +63,23 @@ impl Display for RunnableParams {
     let d = match self {
       RunnableParams::RunFile(_) => "runfile",
       RunnableParams::Shell(_) => "shell",
-      RunnableParams::RustBin(_) => "rust (bin)",
+      RunnableParams::RustBin(params) => match params.target {
+        RustBinTarget::Bin => "rust (bin)",
+        RustBinTarget::Example => "rust (example)",
+        RustBinTarget::Test => "rust (test)",
+        RustBinTarget::Bench => "rust (bench)",
+      },
+      RunnableParams::RustLib(params) if params.proc_macro => "rust (proc-macro)",
       RunnableParams::RustLib(_) => "rust (lib)",
+      RunnableParams::RustWorkspace(_) => "rust (workspace)",
+      RunnableParams::CargoAlias(params) if params.xtask => "cargo (xtask)",
+      RunnableParams::CargoAlias(_) => "cargo (alias)",
+      RunnableParams::Javascript(_) => "javascript",
+      RunnableParams::Make(_) => "make",
+      RunnableParams::Just(_) => "just",
+      RunnableParams::Taskfile(_) => "taskfile",
+      RunnableParams::Python(_) => "python",
+      RunnableParams::Go(_) => "go",
       RunnableParams::None => "none",
     };
     f.write_str(d)
@@ -49,7 +87,36 @@ impl Display for RunnableParams {
 }
 
 impl Runnable {
-  pub fn log_info(&self) {
+  /// Whether the prerequisites which don't depend on each other should run at the same time.
+  pub fn parallel(&self) -> bool {
+    matches!(
+      &self.params,
+      RunnableParams::RunFile(RunFileParams { parallel: true, .. })
+    )
+  }
+
+  /// Whether a failure of this runnable in an `after` chain lets the chain continue.
+  pub fn continue_on_error(&self) -> bool {
+    matches!(
+      &self.params,
+      RunnableParams::RunFile(RunFileParams {
+        continue_on_error: true,
+        ..
+      })
+    )
+  }
+
+  /// Fills in the values of the declared runfile params, see [RunFileParams::resolve],
+  /// or of the just recipe parameters, see [JustParams::resolve].
+  pub fn resolve_params(&mut self, given: &[(String, String)]) -> anyhow::Result<()> {
+    match &mut self.params {
+      RunnableParams::RunFile(params) => params.resolve(&self.name, given),
+      RunnableParams::Just(params) => params.resolve(&self.name, given),
+      _ => Ok(()),
+    }
+  }
+
+  pub fn log_info(&self, args: &[String]) {
     println!("-----------------------");
     println!("running: {}", self.name.bright_blue());
     println!("type: {}", self.params.to_string().bright_blue());
@@ -58,10 +125,55 @@ impl Runnable {
       println!("after: {}", format!("{after:?}").bright_blue());
     }
 
+    if let RunnableParams::Shell(ShellParams {
+      shebang: Some(shebang),
+    }) = &self.params
+    {
+      println!("shell: {}", shebang.bright_blue());
+    }
+
+    if let RunnableParams::RunFile(params) = &self.params {
+      if let Some(shell) = &params.shell {
+        println!("shell: {}", shell.bright_blue());
+      }
+      if !params.values.is_empty() {
+        let shell = params.shell(config().shell.as_deref());
+        println!("command: {}", params.command_line(shell).bright_blue());
+      }
+      if !params.env.is_empty() {
+        let keys = params.env.keys().cloned().collect::<Vec<_>>();
+        println!("env: {}", keys.join(", ").bright_blue());
+      }
+      for env_file in &params.missing_env_files {
+        println!("{} {}", "env_file not found:".red(), env_file.display());
+      }
+    }
+
     if let RunnableParams::RustBin(params) = &self.params {
+      println!("command: {}", params.command_line(&self.name).bright_blue());
+    }
+
+    if let RunnableParams::RustLib(params) = &self.params {
+      println!("command: {}", params.command_line().bright_blue());
+    }
+
+    if let RunnableParams::Javascript(params) = &self.params {
       println!("command: {}", params.command.to_string().bright_blue());
     }
 
+    if let RunnableParams::Go(params) = &self.params {
+      println!("command: {}", params.command.to_string().bright_blue());
+      println!("module: {}", params.module.bright_blue());
+    }
+
+    if let RunnableParams::Python(params) = &self.params {
+      println!("command: {}", params.command_line(&self.name).bright_blue());
+    }
+
+    if !args.is_empty() {
+      println!("args: {}", shell_join(args).bright_blue());
+    }
+
     println!("path: {}", self.path.display().to_string().bright_blue());
     println!("-----------------------\n");
   }